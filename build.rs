@@ -0,0 +1,53 @@
+//! Build script, embeds the git commit and the build date in the version
+
+use std::{
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[path = "src/date.rs"]
+mod date;
+
+/// Short hash of the current git commit, if built from a git checkout
+fn git_short_hash() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!hash.is_empty()).then_some(hash)
+}
+
+/// UTC build date (`YYYY-MM-DD`), honoring `SOURCE_DATE_EPOCH` for reproducible builds
+fn build_date() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+    let (year, month, day) = date::civil_from_days(secs / 86400);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn main() {
+    let crate_version = env!("CARGO_PKG_VERSION");
+    let version = match git_short_hash() {
+        Some(hash) => format!("{crate_version} ({hash} {})", build_date()),
+        None => crate_version.to_string(),
+    };
+    println!("cargo:rustc-env=N4N5_VERSION={version}");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/date.rs");
+    for git_path in [".git/HEAD", ".git/refs"] {
+        if std::path::Path::new(git_path).exists() {
+            println!("cargo:rerun-if-changed={git_path}");
+        }
+    }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
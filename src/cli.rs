@@ -3,7 +3,7 @@
 use clap::Parser;
 use clap::builder::Styles;
 use clap::builder::styling::{AnsiColor, Effects};
-use std::path::PathBuf;
+use std::{io::IsTerminal, path::PathBuf};
 
 use crate::{config::Config, errors::GeneralError};
 
@@ -18,9 +18,11 @@ const STYLES: Styles = Styles::styled()
 
 /// Example CLI using clap derive and subcommands
 #[derive(Parser, Debug)]
-#[command(version, name = "n4n5", about = "n4n5 CLI", long_about = None, styles = STYLES)]
+#[command(version = env!("N4N5_VERSION"), name = "n4n5", about = "n4n5 CLI", long_about = None, styles = STYLES)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct CliArgs {
-    /// Sets a custom config file
+    /// Sets a custom config file, defaults to `$N4N5_CONFIG` then `$XDG_CONFIG_HOME/.n4n5/config.toml`
+    /// (`~/.config/.n4n5/config.toml` when unset)
     #[arg(long, value_name = "FILE")]
     pub config: Option<PathBuf>,
 
@@ -28,10 +30,32 @@ pub struct CliArgs {
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub debug: u8,
 
-    /// whether to use input for configuration
-    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Do not print informational messages, errors are still printed
+    #[arg(short, long, action = clap::ArgAction::SetTrue, global = true)]
+    pub quiet: bool,
+
+    /// Never ask for input, fail instead (for scripts and CI)
+    ///
+    /// Input is also disabled when stdin is not a terminal
+    #[arg(long = "no-input", action = clap::ArgAction::SetFalse)]
     pub use_input: bool,
 
+    /// Deprecated, input is enabled by default, see `--no-input`
+    #[arg(long = "use-input", action = clap::ArgAction::SetTrue, hide = true)]
+    pub legacy_use_input: bool,
+
+    /// Answer yes to every y/n prompt, without reading stdin
+    #[arg(short = 'y', long = "yes", action = clap::ArgAction::SetTrue, global = true)]
+    pub assume_yes: bool,
+
+    /// Disable colored output, also disabled by a non-empty `NO_COLOR` env var
+    #[arg(long = "no-color", action = clap::ArgAction::SetFalse)]
+    pub color: bool,
+
+    /// Ask for input even when stdin is not a terminal
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "use_input")]
+    pub interactive: bool,
+
     /// Subcommands
     #[command(subcommand)]
     pub command: Commands,
@@ -46,9 +70,21 @@ pub fn cli_main() -> Result<(), GeneralError> {
     let CliArgs {
         command,
         use_input,
+        interactive,
+        assume_yes,
+        color,
         debug,
+        quiet,
         config,
+        legacy_use_input,
     } = cli_args;
-    let mut config = Config::try_new(config, debug, use_input)?;
+    if legacy_use_input && !quiet {
+        eprintln!("Warning: --use-input is deprecated, input is enabled by default");
+    }
+    let use_input = interactive || (use_input && std::io::stdin().is_terminal());
+    // see https://no-color.org
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let color = color && !no_color_env;
+    let mut config = Config::try_new(config, debug, use_input, quiet, assume_yes, color)?;
     command.invoke(&mut config)
 }
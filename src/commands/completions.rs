@@ -0,0 +1,104 @@
+//! completions commands
+
+use clap::{CommandFactory, ValueEnum};
+use clap_complete::{
+    Generator, generate, generate_to,
+    shells::{Bash, Elvish, Fish, PowerShell, Zsh},
+};
+use std::{
+    io::{ErrorKind, Write},
+    path::PathBuf,
+};
+
+use crate::cli::CliArgs;
+use crate::commands::Commands;
+use crate::config::Config;
+use crate::errors::GeneralError;
+
+/// Shell to generate the completion for
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum CompletionShell {
+    /// Bash
+    Bash,
+
+    /// Zsh
+    Zsh,
+
+    /// Fish
+    Fish,
+
+    /// PowerShell
+    #[value(name = "powershell")]
+    PowerShell,
+
+    /// Elvish
+    Elvish,
+
+    /// Fig (Amazon Q) spec
+    #[cfg(feature = "fig")]
+    Fig,
+}
+
+impl Generator for CompletionShell {
+    fn file_name(&self, name: &str) -> String {
+        match self {
+            Self::Bash => Bash.file_name(name),
+            Self::Zsh => Zsh.file_name(name),
+            Self::Fish => Fish.file_name(name),
+            Self::PowerShell => PowerShell.file_name(name),
+            Self::Elvish => Elvish.file_name(name),
+            #[cfg(feature = "fig")]
+            Self::Fig => clap_complete_fig::Fig.file_name(name),
+        }
+    }
+
+    fn generate(&self, cmd: &clap::Command, buf: &mut dyn std::io::Write) {
+        match self {
+            Self::Bash => Bash.generate(cmd, buf),
+            Self::Zsh => Zsh.generate(cmd, buf),
+            Self::Fish => Fish.generate(cmd, buf),
+            Self::PowerShell => PowerShell.generate(cmd, buf),
+            Self::Elvish => Elvish.generate(cmd, buf),
+            #[cfg(feature = "fig")]
+            Self::Fig => clap_complete_fig::Fig.generate(cmd, buf),
+        }
+    }
+}
+
+impl Commands {
+    /// Generate the completions of `shell`, or of all shells, in `custom_dir` or the config dir
+    ///
+    /// With `stdout`, the completion of `shell` is printed instead
+    /// # Errors
+    /// Fails if the home dir cannot be found or the files cannot be written
+    pub fn gen_completions(
+        _config: &mut Config,
+        shell: Option<CompletionShell>,
+        stdout: bool,
+        custom_dir: Option<PathBuf>,
+    ) -> Result<(), GeneralError> {
+        let mut cmd = CliArgs::command();
+        let app_name = env!("CARGO_CRATE_NAME");
+        if stdout && let Some(shell) = shell {
+            let mut buf = Vec::new();
+            generate(shell, &mut cmd, app_name, &mut buf);
+            // a closed pipe (`| head`) is not an error
+            return match std::io::stdout().lock().write_all(&buf) {
+                Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+        let outdir = Commands::generated_dir(custom_dir, "completions")?;
+        if let Some(shell) = shell {
+            generate_to(shell, &mut cmd, app_name, &outdir)?;
+            return Ok(());
+        }
+        generate_to(Bash, &mut cmd, app_name, &outdir)?;
+        generate_to(Zsh, &mut cmd, app_name, &outdir)?;
+        generate_to(Fish, &mut cmd, app_name, &outdir)?;
+        generate_to(PowerShell, &mut cmd, app_name, &outdir)?;
+        generate_to(Elvish, &mut cmd, app_name, &outdir)?;
+
+        Ok(())
+    }
+}
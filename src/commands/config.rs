@@ -1,10 +1,17 @@
 //! config command
 
 use clap::{ArgAction, Subcommand};
-use std::process::Command;
+use std::{
+    fs::{OpenOptions, read_to_string},
+    path::Path,
+    process::Command,
+};
 
-use crate::config::Config;
+use crate::commands::gh::lib::Gh;
+use crate::commands::{movies::Movies, music::MusicCliCommand};
+use crate::config::{CONFIG_TEMPLATE, Config, ConfigData};
 use crate::errors::GeneralError;
+use crate::utils::resolve_editor;
 
 /// Config subcommand
 #[derive(Subcommand, Debug, Clone)]
@@ -15,8 +22,46 @@ pub enum ConfigSubcommand {
         #[arg(short = 'p', long = "path", action = ArgAction::SetTrue)]
         show_path_only: bool,
     },
+
+    /// Print the path of the config file
+    Path,
+
+    /// Print the effective configuration
+    Dump {
+        /// Print as JSON
+        #[arg(short = 'j', long = "json", action = ArgAction::SetTrue)]
+        print_json: bool,
+    },
+
+    /// Check that the configured paths exist and are writable
+    Validate,
+
+    /// Write a commented template of the config file
+    Init {
+        /// Overwrite a non-empty config file
+        #[arg(short = 'f', long = "force", action = ArgAction::SetTrue)]
+        force: bool,
+    },
+
+    /// Rename the outdated keys of the config file
+    Migrate,
+}
+
+/// A config key that was renamed
+struct KeyRename {
+    /// Section of the key (`movies`, `gh`, `music`), empty for a top-level key
+    section: &'static str,
+    /// Old name of the key
+    old: &'static str,
+    /// Current name of the key
+    new: &'static str,
 }
 
+/// Keys renamed since they were introduced, applied in order by `config migrate`
+///
+/// Add an entry here whenever a config key is renamed
+const KEY_RENAMES: &[KeyRename] = &[];
+
 impl ConfigSubcommand {
     /// Invoke subcommands
     /// # Errors
@@ -26,7 +71,201 @@ impl ConfigSubcommand {
             ConfigSubcommand::Open { show_path_only } => {
                 ConfigSubcommand::open(config, *show_path_only)
             }
+            ConfigSubcommand::Path => {
+                println!("{}", config.config_path.display());
+                Ok(())
+            }
+            ConfigSubcommand::Dump { print_json } => ConfigSubcommand::dump(config, *print_json),
+            ConfigSubcommand::Validate => ConfigSubcommand::validate(config),
+            ConfigSubcommand::Init { force } => ConfigSubcommand::init(config, *force),
+            ConfigSubcommand::Migrate => ConfigSubcommand::migrate(config),
+        }
+    }
+
+    /// Apply the known key renames to the raw config file and report them
+    ///
+    /// The file is only written when something changed. When both the old and
+    /// the new key are set, the new one is kept and the old one is left untouched
+    /// # Errors
+    /// Return an error if the config file can't be written
+    fn migrate(config: &Config) -> Result<(), GeneralError> {
+        let mut raw = config.raw_data.clone();
+        let changes = apply_renames(&mut raw, KEY_RENAMES);
+        if changes.is_empty() {
+            if !config.quiet {
+                println!("Nothing to migrate in {}", config.config_path.display());
+            }
+            return Ok(());
+        }
+        config.save_data(&raw)?;
+        if !config.quiet {
+            println!("Migrated {}:", config.config_path.display());
+            for change in &changes {
+                println!("- {change}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Print the effective configuration, with the values from the environment
+    /// # Errors
+    /// Return an error if the configuration can't be serialized
+    fn dump(config: &Config, print_json: bool) -> Result<(), GeneralError> {
+        let toml::Value::Table(mut data) = toml::Value::try_from(&config.config_data)? else {
+            return Err(GeneralError::new("Unable to convert the config to a table"));
+        };
+        let mut from_env = Vec::new();
+        let file_token = config
+            .config_data
+            .gh
+            .as_ref()
+            .and_then(|gh| gh.token.as_ref());
+        if file_token.is_none()
+            && let Some(token) = Gh::get_token(config)
+            && let toml::Value::Table(gh) = data
+                .entry("gh")
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        {
+            gh.insert("token".to_string(), toml::Value::String(token));
+            from_env.push("gh.token (GITHUB_TOKEN)");
+        }
+        // never print the token itself
+        if let Some(toml::Value::Table(gh)) = data.get_mut("gh")
+            && let Some(token) = gh.get_mut("token")
+        {
+            *token = toml::Value::String("***".to_string());
+        }
+        if print_json {
+            println!("{}", serde_json::to_string_pretty(&data)?);
+            return Ok(());
+        }
+        println!("# config file: {}", config.config_path.display());
+        for value in from_env {
+            println!("# from env: {value}");
+        }
+        print!("{}", toml::to_string(&data)?);
+        Ok(())
+    }
+
+    /// Check the configured paths, reporting the missing and unwritable ones
+    /// # Errors
+    /// Return an error if any configured path is broken
+    fn validate(config: &Config) -> Result<(), GeneralError> {
+        let data = &config.config_data;
+        let movies = data.movies.as_ref();
+        let gh = data.gh.as_ref();
+        let music = data.music.as_ref();
+        let paths = [
+            (
+                "movies.file_path",
+                movies.and_then(|m| m.file_path.as_ref()),
+            ),
+            (
+                "movies.public_file_path",
+                movies.and_then(|m| m.public_file_path.as_ref()),
+            ),
+            ("gh.file_pulls", gh.and_then(|g| g.file_pulls.as_ref())),
+            (
+                "gh.file_projects",
+                gh.and_then(|g| g.file_projects.as_ref()),
+            ),
+            (
+                "gh.file_projects_disk",
+                gh.and_then(|g| g.file_projects_disk.as_ref()),
+            ),
+            ("gh.file_profile", gh.and_then(|g| g.file_profile.as_ref())),
+            ("gh.file_stars", gh.and_then(|g| g.file_stars.as_ref())),
+            (
+                "music.music_file",
+                music.and_then(|m| m.music_file.as_ref()),
+            ),
+            ("music.env_path", music.and_then(|m| m.env_path.as_ref())),
+        ];
+        let mut broken = 0;
+        for (name, path) in paths {
+            let Some(path) = path else {
+                if !config.quiet {
+                    println!("{name}: not set");
+                }
+                continue;
+            };
+            let (status, is_broken) = ConfigSubcommand::path_status(Path::new(path));
+            if is_broken {
+                broken += 1;
+                eprintln!("{name}: {status} ({path})");
+            } else if !config.quiet {
+                println!("{name}: {status} ({path})");
+            }
+        }
+        if broken > 0 {
+            return Err(GeneralError::config(format!(
+                "{broken} broken paths in config"
+            )));
+        }
+        if !config.quiet {
+            println!("Config is valid");
         }
+        Ok(())
+    }
+
+    /// Get the status of a configured file path, and whether it is broken
+    fn path_status(path: &Path) -> (&'static str, bool) {
+        if path.is_dir() {
+            return ("is a directory", true);
+        }
+        if path.is_file() {
+            return match OpenOptions::new().append(true).open(path) {
+                Ok(_) => ("ok", false),
+                Err(_) => ("unwritable", true),
+            };
+        }
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        if !parent.is_dir() {
+            return ("missing, parent directory does not exist", true);
+        }
+        match parent.metadata() {
+            Ok(metadata) if metadata.permissions().readonly() => {
+                ("missing, parent directory is unwritable", true)
+            }
+            _ => ("missing", false),
+        }
+    }
+
+    /// Write the config template to the config file
+    /// # Errors
+    /// Return an error if the config file is not empty and `force` is not set,
+    /// or if the file can't be written
+    fn init(config: &Config, force: bool) -> Result<(), GeneralError> {
+        let config_path = &config.config_path;
+        let is_empty =
+            read_to_string(config_path).map_or(true, |content| content.trim().is_empty());
+        if !is_empty && !force {
+            return Err(GeneralError::config(format!(
+                "Config file '{}' is not empty, use --force to overwrite it",
+                config_path.display()
+            )));
+        }
+        // JSON has no comments, so a JSON config gets the empty sections only
+        let template = if Config::is_json_path(config_path) {
+            serde_json::to_string_pretty(&ConfigData {
+                movies: Some(Movies::default()),
+                gh: Some(Gh::default()),
+                music: Some(MusicCliCommand::default()),
+                ..Default::default()
+            })?
+        } else {
+            CONFIG_TEMPLATE.to_string()
+        };
+        config
+            .write_atomic(&template)
+            .map_err(|e| format!("Unable to write '{}': {e}", config_path.display()))?;
+        if !config.quiet {
+            println!("Config template written to {}", config_path.display());
+        }
+        Ok(())
     }
 
     /// Open the config file with the default editor
@@ -38,9 +277,108 @@ impl ConfigSubcommand {
             println!("{}", config_path.display());
             return Ok(());
         }
-        println!("Opening config {}", config_path.display());
-        let editor = std::env::var("EDITOR").unwrap_or("vi".to_string());
+        if !config.quiet {
+            println!("Opening config {}", config_path.display());
+        }
+        let editor = resolve_editor(config);
         Command::new(editor).arg(config_path).spawn()?.wait()?;
         Ok(())
     }
 }
+
+/// Apply `renames` in order to a raw config, returning the applied renames
+///
+/// When both the old and the new key are set, the new one is kept
+fn apply_renames(raw: &mut serde_json::Value, renames: &[KeyRename]) -> Vec<String> {
+    let mut changes = Vec::new();
+    for rename in renames {
+        let table = if rename.section.is_empty() {
+            raw.as_object_mut()
+        } else {
+            raw.get_mut(rename.section)
+                .and_then(serde_json::Value::as_object_mut)
+        };
+        let Some(table) = table else {
+            continue;
+        };
+        let prefix = if rename.section.is_empty() {
+            String::new()
+        } else {
+            format!("{}.", rename.section)
+        };
+        if !table.contains_key(rename.old) {
+            continue;
+        }
+        if table.contains_key(rename.new) {
+            eprintln!(
+                "Both '{prefix}{}' and '{prefix}{}' are set, keeping '{prefix}{}'",
+                rename.old, rename.new, rename.new
+            );
+            continue;
+        }
+        if let Some(value) = table.remove(rename.old) {
+            table.insert(rename.new.to_string(), value);
+            changes.push(format!("{prefix}{} -> {prefix}{}", rename.old, rename.new));
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    //! Tests of the config migration
+
+    use super::{KeyRename, apply_renames};
+    use serde_json::json;
+
+    /// Renames used by the tests
+    const RENAMES: &[KeyRename] = &[
+        KeyRename {
+            section: "",
+            old: "editr",
+            new: "editor",
+        },
+        KeyRename {
+            section: "gh",
+            old: "pulls_file",
+            new: "file_pulls",
+        },
+        KeyRename {
+            section: "movies",
+            old: "file",
+            new: "file_path",
+        },
+    ];
+
+    /// Old keys are renamed, at the top level and in sections
+    #[test]
+    fn migrate_renames_old_keys() {
+        let mut raw = json!({
+            "editr": "vi",
+            "gh": {"pulls_file": "pulls.json", "custom": 1},
+        });
+        let changes = apply_renames(&mut raw, RENAMES);
+        assert_eq!(
+            changes,
+            ["editr -> editor", "gh.pulls_file -> gh.file_pulls"]
+        );
+        assert_eq!(
+            raw,
+            json!({
+                "editor": "vi",
+                "gh": {"file_pulls": "pulls.json", "custom": 1},
+            })
+        );
+        assert!(apply_renames(&mut raw, RENAMES).is_empty());
+    }
+
+    /// An existing new key is not overwritten by the old one
+    #[test]
+    fn migrate_keeps_existing_new_key() {
+        let mut raw = json!({"movies": {"file": "old.json", "file_path": "new.json"}});
+        let before = raw.clone();
+        assert!(apply_renames(&mut raw, RENAMES).is_empty());
+        assert_eq!(raw, before);
+    }
+}
@@ -0,0 +1,201 @@
+//! Client for the github graphql api
+
+use reqwest::blocking::Client;
+use serde_json::Value;
+use std::{process::Command, thread, time::Duration};
+
+use crate::{
+    commands::gh::lib::{Gh, ensure_gh_available, get_github_username},
+    config::Config,
+    errors::GeneralError,
+};
+
+/// GitHub GraphQL endpoint, used when a token is available
+const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// Default number of retries when rate limited
+const DEFAULT_RETRIES: u32 = 3;
+
+/// Default base delay (in seconds) before retrying when rate limited
+const DEFAULT_RETRY_DELAY: u64 = 2;
+
+/// Default connect and request timeout (in seconds) of the token requests
+const DEFAULT_TIMEOUT: u64 = 30;
+
+/// GraphQL client, using a token or the gh cli
+pub(crate) struct GraphqlClient {
+    /// Github token, the gh cli is used when not set
+    token: Option<String>,
+
+    /// HTTP client of the token requests, reused across pages and retries
+    http: Option<Client>,
+
+    /// GitHub Enterprise host, github.com when not set
+    host: Option<String>,
+
+    /// Number of retries when rate limited
+    retries: u32,
+
+    /// Base delay (in seconds) before retrying, doubled after each retry
+    retry_delay: u64,
+}
+
+impl GraphqlClient {
+    /// Create the client from the config
+    /// # Errors
+    /// Fails if no token is set and the gh cli is not available
+    pub(crate) fn try_from_config(config: &Config) -> Result<Self, GeneralError> {
+        let client = Self::from_config(config)?;
+        if client.token.is_none() {
+            ensure_gh_available()?;
+        }
+        Ok(client)
+    }
+
+    /// Create the client from the config, without checking that the gh cli is available
+    /// # Errors
+    /// Fails if the HTTP client can't be built
+    pub(crate) fn from_config(config: &Config) -> Result<Self, GeneralError> {
+        let gh_config = config.config_data.gh.as_ref();
+        let token = Gh::get_token(config);
+        let http = match token {
+            Some(_) => {
+                let timeout = Duration::from_secs(
+                    gh_config
+                        .and_then(|gh| gh.timeout)
+                        .unwrap_or(DEFAULT_TIMEOUT),
+                );
+                Some(
+                    Client::builder()
+                        .user_agent("n4n5")
+                        .connect_timeout(timeout)
+                        .timeout(timeout)
+                        .build()?,
+                )
+            }
+            None => None,
+        };
+        Ok(Self {
+            token,
+            http,
+            host: gh_config
+                .and_then(|gh| gh.host.clone())
+                .filter(|host| !host.trim().is_empty()),
+            retries: gh_config
+                .and_then(|gh| gh.retries)
+                .unwrap_or(DEFAULT_RETRIES),
+            retry_delay: gh_config
+                .and_then(|gh| gh.retry_delay)
+                .unwrap_or(DEFAULT_RETRY_DELAY),
+        })
+    }
+
+    /// Describe how queries are sent, without sending anything
+    pub(crate) fn describe(&self) -> String {
+        if self.token.is_some() {
+            let url = self.host.as_ref().map_or_else(
+                || GITHUB_GRAPHQL_URL.to_string(),
+                |host| format!("https://{host}/api/graphql"),
+            );
+            return format!("POST {url} (token)");
+        }
+        match &self.host {
+            Some(host) => format!("gh api --hostname {host} graphql"),
+            None => "gh api graphql".to_string(),
+        }
+    }
+
+    /// Run a GraphQL query with `$owner` set to the github username
+    ///
+    /// Rate limited queries are retried with an exponential backoff
+    /// # Errors
+    /// Fails if the request or the gh command fails
+    pub(crate) fn run(&self, query: &str) -> Result<String, GeneralError> {
+        let mut attempt = 0;
+        loop {
+            let result = self.run_once(query);
+            let rate_limited = match &result {
+                Ok(output) => is_rate_limited(output),
+                Err(e) => e.to_string().to_lowercase().contains("rate limit"),
+            };
+            if !rate_limited || attempt >= self.retries {
+                return result;
+            }
+            let delay = self.retry_delay.saturating_mul(1 << attempt.min(16));
+            attempt += 1;
+            eprintln!(
+                "Rate limited, retrying in {delay}s ({attempt}/{})",
+                self.retries
+            );
+            thread::sleep(Duration::from_secs(delay));
+        }
+    }
+
+    /// Run a GraphQL query once
+    ///
+    /// Uses the GraphQL endpoint directly when a token is set, otherwise the gh cli.
+    /// With a host, queries go to that GitHub Enterprise instance
+    /// # Errors
+    /// Fails if the request or the gh command fails
+    fn run_once(&self, query: &str) -> Result<String, GeneralError> {
+        let owner = get_github_username();
+        if let (Some(token), Some(client)) = (&self.token, &self.http) {
+            let body = serde_json::json!({
+                "query": query,
+                "variables": { "owner": owner },
+            });
+            let url = self.host.as_ref().map_or_else(
+                || GITHUB_GRAPHQL_URL.to_string(),
+                |host| format!("https://{host}/api/graphql"),
+            );
+            let response = client.post(url).bearer_auth(token).json(&body).send()?;
+            let status = response.status();
+            let output = response.text()?;
+            // checked before the status, a rate limited response is retried by `run`
+            if !status.is_success() && !is_rate_limited(&output) {
+                // keep the body, a secondary rate limit is only told apart by its message
+                return Err(GeneralError::new(format!(
+                    "GitHub API returned {status}: {}",
+                    output.trim()
+                )));
+            }
+            return Ok(output);
+        }
+        let mut command = Command::new("gh");
+        command.arg("api");
+        if let Some(host) = &self.host {
+            command.arg("--hostname").arg(host);
+        }
+        let output = command
+            .arg("graphql")
+            .arg("-F")
+            .arg(format!("owner={owner}"))
+            .arg("-f")
+            .arg(format!("query={query}"))
+            .output()?;
+        if !output.status.success() {
+            return Err(GeneralError::new(format!(
+                "gh command failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// Check if a GraphQL response contains a rate limit error
+fn is_rate_limited(output: &str) -> bool {
+    let Ok(Value::Object(map)) = serde_json::from_str::<Value>(output) else {
+        return false;
+    };
+    let Some(Value::Array(errors)) = map.get("errors") else {
+        return false;
+    };
+    errors.iter().any(|error| {
+        error.get("type").and_then(Value::as_str) == Some("RATE_LIMITED")
+            || error
+                .get("message")
+                .and_then(Value::as_str)
+                .is_some_and(|message| message.to_lowercase().contains("rate limit"))
+    })
+}
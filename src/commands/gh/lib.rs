@@ -3,10 +3,16 @@
 //! n4n5 gh
 //! ```
 
-use clap::{ArgAction, Subcommand};
+use clap::{ArgAction, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{collections::BTreeMap, fs::write, path::PathBuf, process::Command};
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 use crate::{
     commands::gh::types::GhProject,
@@ -14,16 +20,32 @@ use crate::{
     config_path,
     errors::GeneralError,
     get_config_path,
-    utils::{input_path, pretty_print},
+    utils::{input_path, pretty_print, table_to_markdown_table, utc_now_iso},
 };
 
-use super::types::{GhPageInfo, GhResponse};
+use super::graphql::GraphqlClient;
+use super::queries::{MAX_PAGE_SIZE, ProjectType, projects_query, pulls_query};
+use super::types::{
+    GhPageInfo, GhProfile, GhProjectsSummary, GhPullRequest, GhPullRequests, GhResponse,
+};
 
 /// Get github username
 pub(crate) fn get_github_username() -> String {
     "Its-Just-Nans".to_string()
 }
 
+/// Check that the gh cli is installed
+/// # Errors
+/// Fails if `gh --version` can't be run
+pub(crate) fn ensure_gh_available() -> Result<(), GeneralError> {
+    match Command::new("gh").arg("--version").output() {
+        Ok(output) if output.status.success() => Ok(()),
+        _ => Err(GeneralError::new(
+            "GitHub CLI (gh) is not installed or not in PATH",
+        )),
+    }
+}
+
 /// Github configuration
 #[derive(Deserialize, Serialize, Default)]
 pub struct Gh {
@@ -38,6 +60,33 @@ pub struct Gh {
 
     /// Path to the projects file disk usage
     pub file_projects_disk: Option<String>,
+
+    /// Path to the profile file
+    pub file_profile: Option<String>,
+
+    /// Path to the starred repositories file
+    pub file_stars: Option<String>,
+
+    /// Github token, the gh cli is used when not set
+    pub token: Option<String>,
+
+    /// GitHub Enterprise host, github.com when not set
+    pub host: Option<String>,
+
+    /// Number of retries when rate limited
+    pub retries: Option<u32>,
+
+    /// Base delay (in seconds) before retrying when rate limited
+    pub retry_delay: Option<u64>,
+
+    /// Connect and request timeout (in seconds) when using the token
+    pub timeout: Option<u64>,
+
+    /// UTC time of the last successful pulls sync
+    pub pulls_synced_at: Option<String>,
+
+    /// UTC time of the last successful projects sync
+    pub projects_synced_at: Option<String>,
 }
 
 /// Github subcommands
@@ -48,6 +97,41 @@ pub enum GhSubCommand {
         /// Print as JSON
         #[arg(short = 'j', long = "json", action = ArgAction::SetTrue)]
         print_json: bool,
+
+        /// Resume pagination after this cursor, appending to the pulls file
+        #[arg(long = "after-cursor", value_name = "CURSOR")]
+        after_cursor: Option<String>,
+
+        /// Number of languages to fetch per base repository
+        #[arg(long = "languages", default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=100))]
+        languages: u8,
+
+        /// Fetch the labels of the pulls
+        #[arg(long = "enrich-labels", action = ArgAction::SetTrue)]
+        enrich_labels: bool,
+
+        /// Output file (`-` for stdout), instead of the configured pulls file
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Only keep pulls in these states (comma-separated), all by default
+        #[arg(long = "state", value_delimiter = ',', value_name = "STATE")]
+        states: Vec<PullState>,
+
+        /// Only fetch pulls created since this ISO date (`YYYY-MM-DD`)
+        ///
+        /// Pulls are fetched newest first and pagination stops at the first
+        /// older pull. Whole pages of `--page-size` pulls are still fetched before filtering
+        #[arg(long = "since", value_name = "DATE", value_parser = parse_iso_date)]
+        since: Option<String>,
+
+        /// Number of pulls fetched per request (1 to 100)
+        #[arg(long = "page-size", default_value_t = MAX_PAGE_SIZE, value_parser = clap::value_parser!(u8).range(1..=100))]
+        page_size: u8,
+
+        /// Only keep pulls against this repository (`owner/name`), can be repeated
+        #[arg(long = "repo", value_name = "OWNER/NAME", value_parser = parse_repo)]
+        repos: Vec<String>,
     },
 
     /// Save projects
@@ -55,7 +139,56 @@ pub enum GhSubCommand {
         /// Print as JSON
         #[arg(short = 'j', long = "json", action = ArgAction::SetTrue)]
         print_json: bool,
+
+        /// Include forked repositories
+        #[arg(long = "include-forks", action = ArgAction::SetTrue)]
+        include_forks: bool,
+
+        /// Only keep repositories with this primary language (gists are skipped)
+        #[arg(long = "language", value_name = "LANGUAGE")]
+        language: Option<String>,
+
+        /// Output file (`-` for stdout), instead of the configured projects file
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Number of projects fetched per request (1 to 100)
+        #[arg(long = "page-size", default_value_t = MAX_PAGE_SIZE, value_parser = clap::value_parser!(u8).range(1..=100))]
+        page_size: u8,
+
+        /// Print language names of the summary in their github color (truecolor terminals)
+        #[arg(long = "color", action = ArgAction::SetTrue)]
+        color: bool,
+
+        /// Show the saved projects file (or `--output`) as a table, without fetching
+        #[arg(long = "show", action = ArgAction::SetTrue)]
+        show: bool,
+    },
+
+    /// Save pulls, projects and languages in a single profile file
+    Profile {
+        /// Print as JSON
+        #[arg(short = 'j', long = "json", action = ArgAction::SetTrue)]
+        print_json: bool,
+
+        /// Output file (`-` for stdout)
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
     },
+
+    /// Save starred repositories
+    Stars {
+        /// Print as JSON
+        #[arg(short = 'j', long = "json", action = ArgAction::SetTrue)]
+        print_json: bool,
+
+        /// Output file (`-` for stdout)
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Show the configured files and when they were last synced
+    Status,
 }
 
 impl GhSubCommand {
@@ -64,33 +197,372 @@ impl GhSubCommand {
     /// Error if error in subcommand
     pub fn invoke(self, config: &mut Config) -> Result<(), GeneralError> {
         match self {
-            Self::Projects { print_json } => {
-                Gh::pre_save_projects(config)?;
-                Gh::save_projects(config, print_json)
+            Self::Projects {
+                print_json,
+                include_forks,
+                language,
+                output,
+                page_size,
+                color,
+                show,
+            } => {
+                if show {
+                    return Gh::show_projects(config, output.as_deref());
+                }
+                let to_config_file = output.is_none();
+                if to_config_file {
+                    Gh::pre_save_projects(config)?;
+                }
+                Gh::save_projects(
+                    config,
+                    print_json,
+                    include_forks,
+                    language.as_deref(),
+                    output.as_deref(),
+                    color,
+                    page_size,
+                )?;
+                if to_config_file {
+                    Gh::record_sync(config, SyncedData::Projects)?;
+                }
+                Ok(())
             }
-            Self::Pulls { print_json: _ } => {
-                Gh::pre_save_pulls(config)?;
-                Gh::save_pulls(config)
+            Self::Pulls {
+                print_json,
+                after_cursor,
+                languages,
+                enrich_labels,
+                output,
+                states,
+                since,
+                repos,
+                page_size,
+            } => {
+                let output = if print_json {
+                    Some(PathBuf::from("-"))
+                } else {
+                    output
+                };
+                if output.is_none() {
+                    Gh::pre_save_pulls(config)?;
+                }
+                let options = PullsQueryOptions {
+                    languages,
+                    labels: enrich_labels,
+                    newest_first: since.is_some(),
+                    page_size,
+                };
+                let filter = PullsFilter {
+                    states,
+                    since,
+                    repos,
+                };
+                Gh::save_pulls(config, after_cursor, options, output.as_deref(), &filter)?;
+                // a filtered fetch only refreshes part of the pulls
+                if output.is_none() && !filter.is_active() {
+                    Gh::record_sync(config, SyncedData::Pulls)?;
+                }
+                Ok(())
+            }
+            Self::Profile { print_json, output } => {
+                let profile_path = match output {
+                    _ if print_json => PathBuf::from("-"),
+                    Some(output) => output,
+                    None => config_path!(config, gh, Gh, file_profile, "profile file"),
+                };
+                Gh::save_profile(config, &profile_path)
+            }
+            Self::Stars { print_json, output } => {
+                let stars_path = match output {
+                    _ if print_json => PathBuf::from("-"),
+                    Some(output) => output,
+                    None => config_path!(config, gh, Gh, file_stars, "stars file"),
+                };
+                Gh::save_stars(config, &stars_path)
+            }
+            Self::Status => {
+                Gh::print_status(config);
+                Ok(())
             }
         }
     }
 }
 
-/// Project type
-enum ProjectType {
-    /// Gists
-    Gists,
-    /// Repos
-    Repos,
+/// Options of the pulls query
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PullsQueryOptions {
+    /// Number of languages to fetch per base repository
+    pub languages: u8,
+
+    /// Fetch the labels of the pulls
+    pub labels: bool,
+
+    /// Fetch the most recently created pulls first
+    pub newest_first: bool,
+
+    /// Number of pulls fetched per request
+    pub page_size: u8,
+}
+
+impl Default for PullsQueryOptions {
+    fn default() -> Self {
+        Self {
+            languages: 1,
+            labels: false,
+            newest_first: false,
+            page_size: MAX_PAGE_SIZE,
+        }
+    }
+}
+
+/// Github data that can be synced
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SyncedData {
+    /// Pull requests
+    Pulls,
+
+    /// Projects
+    Projects,
+}
+
+/// Filters applied to the fetched pulls
+#[derive(Default, Debug)]
+pub(crate) struct PullsFilter {
+    /// Only keep pulls in these states, all if empty
+    pub states: Vec<PullState>,
+
+    /// Only keep pulls created since this ISO date
+    pub since: Option<String>,
+
+    /// Only keep pulls against these repositories (`owner/name`), all if empty
+    pub repos: Vec<String>,
+}
+
+impl PullsFilter {
+    /// Check if the filter drops any pull
+    pub(crate) fn is_active(&self) -> bool {
+        !self.states.is_empty() || self.since.is_some() || !self.repos.is_empty()
+    }
+
+    /// Check if a pull is kept by the filter
+    fn keeps(&self, pull: &GhPullRequest) -> bool {
+        let state_ok =
+            self.states.is_empty() || self.states.iter().any(|s| s.as_api_str() == pull.state());
+        let repo_ok = self.repos.is_empty() || {
+            let repo = pull.base_repository_full_name();
+            self.repos.iter().any(|r| r.eq_ignore_ascii_case(&repo))
+        };
+        state_ok && repo_ok && !self.is_too_old(pull)
+    }
+
+    /// Check if a pull was created before the `since` date
+    fn is_too_old(&self, pull: &GhPullRequest) -> bool {
+        // ISO 8601 dates compare chronologically as strings
+        self.since
+            .as_deref()
+            .is_some_and(|since| pull.created_at() < since)
+    }
+}
+
+/// Parse a repository as `owner/name`
+/// # Errors
+/// Fails if the value is not `owner/name`
+fn parse_repo(value: &str) -> Result<String, String> {
+    match value.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+            Ok(value.to_string())
+        }
+        _ => Err(format!("'{value}' is not a repository (owner/name)")),
+    }
+}
+
+/// Parse an ISO date (`YYYY-MM-DD`, optionally followed by a time)
+/// # Errors
+/// Fails if the value doesn't start with a `YYYY-MM-DD` date
+fn parse_iso_date(value: &str) -> Result<String, String> {
+    let is_date = value.len() >= 10
+        && value.char_indices().take(10).all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+    if is_date {
+        Ok(value.to_string())
+    } else {
+        Err(format!("'{value}' is not an ISO date (YYYY-MM-DD)"))
+    }
+}
+
+/// State of a pull request
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PullState {
+    /// Open pulls
+    Open,
+
+    /// Closed pulls, without the merged ones
+    Closed,
+
+    /// Merged pulls
+    Merged,
+}
+
+impl PullState {
+    /// State as returned by the GraphQL API
+    fn as_api_str(self) -> &'static str {
+        match self {
+            Self::Open => "OPEN",
+            Self::Closed => "CLOSED",
+            Self::Merged => "MERGED",
+        }
+    }
 }
 
 impl Gh {
-    /// Pre Sync the github data
+    /// Get the github token, from the config or from the `GITHUB_TOKEN` env var
+    pub(crate) fn get_token(config: &Config) -> Option<String> {
+        config
+            .config_data
+            .gh
+            .as_ref()
+            .and_then(|gh| gh.token.clone())
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .filter(|token| !token.trim().is_empty())
+    }
+
+    /// Pre Sync one kind of github data
+    ///
+    /// With `dry_run`, the gh cli is not checked
+    /// # Errors
+    /// Fails if the gh cli is missing, or if the output files are not set
+    pub(crate) fn pre_sync_github(
+        config: &mut Config,
+        data: SyncedData,
+        dry_run: bool,
+    ) -> Result<(), GeneralError> {
+        if !dry_run && Gh::get_token(config).is_none() {
+            ensure_gh_available()?;
+        }
+        match data {
+            SyncedData::Pulls => Gh::pre_save_pulls(config),
+            SyncedData::Projects => Gh::pre_save_projects(config),
+        }
+    }
+
+    /// Print a saved projects file as a markdown table
+    ///
+    /// Reads `path`, or the configured projects file
+    /// # Errors
+    /// Fails if the file is not set, can't be read or isn't a projects file
+    fn show_projects(config: &Config, path: Option<&Path>) -> Result<(), GeneralError> {
+        let projects_path = match path {
+            Some(path) => path.to_path_buf(),
+            None => get_config_path!(config, gh, Gh, file_projects, "projects file")?,
+        };
+        let content = read_to_string(&projects_path).map_err(|e| {
+            GeneralError::new_with_source(
+                format!("Unable to read '{}'", projects_path.display()),
+                e,
+            )
+        })?;
+        let projects: Vec<GhProject> = serde_json::from_str(&content)?;
+        let header = ["Name", "Stars", "Language", "Url"]
+            .map(String::from)
+            .to_vec();
+        let rows = projects.iter().map(|p| {
+            vec![
+                p.name.clone(),
+                p.stargazer_count.to_string(),
+                p.primary_language
+                    .as_ref()
+                    .map(|l| l.name.clone())
+                    .unwrap_or_default(),
+                p.url.clone(),
+            ]
+        });
+        print!(
+            "{}",
+            table_to_markdown_table(std::iter::once(header).chain(rows), 4)?
+        );
+        Ok(())
+    }
+
+    /// Print the configured pulls and projects files, with their freshness
+    ///
+    /// Missing settings or files are reported, never an error
+    fn print_status(config: &Config) {
+        let gh = config.config_data.gh.as_ref();
+        let entries = [
+            (
+                "pulls",
+                gh.and_then(|gh| gh.file_pulls.as_deref()),
+                gh.and_then(|gh| gh.pulls_synced_at.as_deref()),
+            ),
+            (
+                "projects",
+                gh.and_then(|gh| gh.file_projects.as_deref()),
+                gh.and_then(|gh| gh.projects_synced_at.as_deref()),
+            ),
+        ];
+        for (name, path, synced_at) in entries {
+            println!("{name}:");
+            let Some(path) = path else {
+                println!("  path: not set");
+                println!("  last synced: {}", synced_at.unwrap_or("never synced"));
+                continue;
+            };
+            println!("  path: {path}");
+            let path = Path::new(path);
+            if path.is_file() {
+                let size = path.metadata().map_or(0, |m| m.len());
+                let records = read_to_string(path)
+                    .ok()
+                    .and_then(|content| serde_json::from_str::<Vec<Value>>(&content).ok())
+                    .map_or("unreadable".to_string(), |records| {
+                        format!("{} records", records.len())
+                    });
+                println!("  file: {size} bytes, {records}");
+            } else {
+                println!("  file: missing");
+            }
+            println!("  last synced: {}", synced_at.unwrap_or("never synced"));
+        }
+    }
+
+    /// Record the current UTC time as the last sync of `data`
     /// # Errors
     /// Fails if unable to save config
-    pub fn pre_sync_github(config: &mut Config) -> Result<(), GeneralError> {
-        Gh::pre_save_pulls(config)?;
-        Gh::pre_save_projects(config)?;
+    pub(crate) fn record_sync(config: &mut Config, data: SyncedData) -> Result<(), GeneralError> {
+        let now = utc_now_iso();
+        config.update(|config_data| {
+            let gh = config_data.gh.get_or_insert_with(Gh::default);
+            match data {
+                SyncedData::Pulls => gh.pulls_synced_at = Some(now),
+                SyncedData::Projects => gh.projects_synced_at = Some(now),
+            }
+        })
+    }
+
+    /// Print what syncing `data` would do, without querying github or writing files
+    /// # Errors
+    /// Fails if the output file is not set
+    pub(crate) fn dry_run_sync(config: &Config, data: SyncedData) -> Result<(), GeneralError> {
+        let (name, paths) = match data {
+            SyncedData::Pulls => (
+                "pulls",
+                vec![get_config_path!(config, gh, Gh, file_pulls, "pulls file")?],
+            ),
+            SyncedData::Projects => (
+                "projects",
+                vec![
+                    get_config_path!(config, gh, Gh, file_projects, "projects file")?,
+                    get_config_path!(config, gh, Gh, file_projects_disk, "projects file")?,
+                ],
+            ),
+        };
+        let client = GraphqlClient::from_config(config)?;
+        println!("Would fetch {name} with `{}`", client.describe());
+        for path in paths {
+            println!("Would write '{}'", path.display());
+        }
         Ok(())
     }
 
@@ -102,163 +574,151 @@ impl Gh {
         Ok(())
     }
 
-    /// Save the pulls to the specified file
+    /// Save the pulls to `output` (`-` for stdout), or to the configured pulls file
+    ///
+    /// When `after_cursor` is set, pagination resumes from that cursor and
+    /// the fetched pulls are appended to the existing file.
+    /// Only the fetched pulls kept by `filter` are written, with an active filter
+    /// they are merged into the existing file instead of replacing it
     /// # Errors
     /// Fails if unable to write to file
-    pub(crate) fn save_pulls(config: &Config) -> Result<(), GeneralError> {
-        let pulls_path = get_config_path!(config, gh, Gh, file_pulls, "pulls file")?;
-        println!("Saving pulls to {}", pulls_path.display());
+    pub(crate) fn save_pulls(
+        config: &Config,
+        after_cursor: Option<String>,
+        options: PullsQueryOptions,
+        output: Option<&Path>,
+        filter: &PullsFilter,
+    ) -> Result<(), GeneralError> {
+        let keep = |pull: &GhPullRequest| filter.keeps(pull);
+        let pulls_path = match output {
+            Some(output) => output.to_path_buf(),
+            None => get_config_path!(config, gh, Gh, file_pulls, "pulls file")?,
+        };
+        let is_stdout = pulls_path == Path::new("-");
+        let quiet = config.quiet || is_stdout;
+        let client = GraphqlClient::try_from_config(config)?;
+        if !quiet {
+            println!("Saving pulls to {}", pulls_path.display());
+        }
+        let mut existing_pulls: Vec<GhPullRequest> = Vec::new();
+        let mut all_pulls: Vec<GhPullRequest> = Vec::new();
         let mut response_data = GhPageInfo {
             has_next_page: true,
             ..Default::default()
         };
-        let mut all_pulls = Vec::new();
+        // a filtered fetch is merged into the saved pulls, so it never drops history
+        if (after_cursor.is_some() || filter.is_active()) && !is_stdout && pulls_path.is_file() {
+            let content = read_to_string(&pulls_path)?;
+            if !content.trim().is_empty() {
+                existing_pulls = serde_json::from_str(&content)?;
+            }
+        }
+        if let Some(cursor) = after_cursor {
+            if !quiet {
+                println!(
+                    "Resuming after cursor '{cursor}' with {} existing pulls",
+                    existing_pulls.len()
+                );
+            }
+            response_data.end_cursor = cursor;
+        }
         while response_data.has_next_page {
-            let add = if response_data.end_cursor.trim().is_empty() {
-                String::new()
-            } else {
-                format!(", after: \"{}\"", response_data.end_cursor)
-            };
-            let command = "gh api graphql -F owner='Its-Just-Nans' -f query='
-    query($owner: String!) {
-        user(login: $owner) {
-            pullRequests(first: 100) {
-                edges {
-                    node {
-                        id
-                        number
-                        title
-                        url
-                        state
-                        createdAt
-                        baseRepository {
-                            url
-                            name
-                            description
-                            owner {
-                            login
-                            }
-                            languages(first: 1) {
-                                nodes {
-                                    name
-                                    color
-                                }
-                            }
+            let pull_requests = match Gh::fetch_pulls_page(
+                &response_data.end_cursor,
+                options,
+                &client,
+                config.debug,
+            ) {
+                Ok(pull_requests) => pull_requests,
+                Err(e) => {
+                    if !response_data.end_cursor.trim().is_empty() {
+                        if !is_stdout {
+                            all_pulls.retain(keep);
+                            let all_pulls = GhPullRequest::merge(existing_pulls, all_pulls);
+                            pretty_print(&all_pulls, &pulls_path)?;
                         }
+                        eprintln!(
+                            "Interrupted, resume with: --after-cursor '{}'",
+                            response_data.end_cursor
+                        );
                     }
+                    return Err(e);
                 }
-                pageInfo {
-                    endCursor
-                    startCursor
-                    hasNextPage
-                    hasPreviousPage
-                }
-            }
-        }
-    }'"
-            .replace("100)", format!("100{add})").as_str());
-            if config.debug > 0 {
-                println!("Running command:");
-                println!("{command}");
+            };
+            if !quiet {
+                println!("Received {} pulls requests", pull_requests.edges.len());
             }
-            let output = Command::new("sh").arg("-c").arg(command).output()?;
-            let output = String::from_utf8_lossy(&output.stdout).to_string();
-            if config.debug > 1 {
-                println!("Output:");
-                println!("{output}");
+            let reached_since = pull_requests.edges.iter().any(|p| filter.is_too_old(p));
+            all_pulls.extend(pull_requests.edges);
+            response_data = pull_requests.page_info;
+            if options.newest_first && reached_since {
+                response_data.has_next_page = false;
             }
-            let output = serde_json::from_str::<GhResponse>(&output)?;
+        }
+        all_pulls.retain(keep);
+        let all_pulls = GhPullRequest::merge(existing_pulls, all_pulls);
+        pretty_print(&all_pulls, &pulls_path)?;
+        if !quiet {
             println!(
-                "Received {} pulls requests",
-                output.data.user.pull_requests.edges.len()
+                "Saving {} pulls to {}",
+                all_pulls.len(),
+                pulls_path.display()
             );
-            all_pulls.extend(output.data.user.pull_requests.edges);
-            response_data = output.data.user.pull_requests.page_info;
         }
-        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
-        let mut buf = Vec::new();
-        let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
-        all_pulls.serialize(&mut ser)?;
-        write(&pulls_path, buf)?;
-        println!(
-            "Saving {} pulls to {}",
-            all_pulls.len(),
-            pulls_path.display()
-        );
         Ok(())
     }
 
-    /// Fetch projects with gh cli
+    /// Fetch one page of pulls
+    /// # Errors
+    /// Fails if the query fails or if the output can't be parsed
+    fn fetch_pulls_page(
+        end_cursor: &str,
+        options: PullsQueryOptions,
+        client: &GraphqlClient,
+        debug: u8,
+    ) -> Result<GhPullRequests, GeneralError> {
+        let query = pulls_query(end_cursor, options);
+        if debug > 0 {
+            println!("Running query:");
+            println!("{query}");
+        }
+        let output = client.run(&query)?;
+        if debug > 1 {
+            println!("Output:");
+            println!("{output}");
+        }
+        let output = serde_json::from_str::<GhResponse>(&output)?;
+        Ok(output.data.user.pull_requests)
+    }
+
+    /// Fetch projects
     /// # Errors
     /// Fails if unable to fetch the projects
     fn fetch_projects(
         project_type: &ProjectType,
+        client: &GraphqlClient,
         debug: u8,
+        include_forks: bool,
+        page_size: u8,
     ) -> Result<Vec<GhProject>, GeneralError> {
         let mut response_data = GhPageInfo {
             has_next_page: true,
             ..Default::default()
         };
-        let fetch_type = match project_type {
-            ProjectType::Gists => "gists",
-            ProjectType::Repos => "repositories",
-        };
-        let repo_arg = match project_type {
-            ProjectType::Gists => "",
-            ProjectType::Repos => "isFork: false, ownerAffiliations: [OWNER]",
-        };
-        let repo_data = match project_type {
-            ProjectType::Gists => "",
-            ProjectType::Repos => {
-                "primaryLanguage {
-                        name
-                        color
-                    }
-                    archivedAt
-                    homepageUrl
-                    diskUsage
-                    forkCount
-                    licenseInfo {
-                        name
-                    }"
-            }
-        };
+        let fetch_type = project_type.field();
         let mut all_projects = Vec::new();
         while response_data.has_next_page {
-            let add = if response_data.end_cursor.trim().is_empty() {
-                String::new()
-            } else {
-                format!(", after: \"{}\", ", response_data.end_cursor)
-            };
-            let command = "gh api graphql -F owner='Its-Just-Nans' -f query='
-    query( $owner: String!){
-        user(login: $owner) {
-            TYPE(first: 100,ADD REPO_ARG, privacy: PUBLIC) {
-                pageInfo {
-                    hasNextPage
-                    endCursor
-                    startCursor
-                }
-                nodes {
-                    url
-                    name
-                    REPO_DATA
-                    description
-                    stargazerCount
-                }
-            }
-        }
-    }'"
-            .replace("TYPE", fetch_type)
-            .replace(",ADD", &add)
-            .replace("REPO_ARG", repo_arg)
-            .replace("REPO_DATA", repo_data);
+            let query = projects_query(
+                project_type,
+                &response_data.end_cursor,
+                page_size,
+                include_forks,
+            );
             if debug > 1 {
-                println!("Running command:");
-                println!("{command}");
+                println!("Running query:");
+                println!("{query}");
             }
-            let output = Command::new("sh").arg("-c").arg(command).output()?;
-            let output = String::from_utf8_lossy(&output.stdout).to_string();
+            let output = client.run(&query)?;
             if debug > 2 {
                 println!("Output:");
                 println!("{output}");
@@ -283,12 +743,15 @@ impl Gh {
                             .clone(),
                     )?;
                 } else {
-                    eprintln!("gh command faileds: {output}");
-                    break;
+                    let errors = map.get("errors").unwrap_or(&output);
+                    return Err(GeneralError::new(format!(
+                        "Unable to fetch {fetch_type}: {errors}"
+                    )));
                 }
             } else {
-                eprintln!("Unable to parse json from gh command");
-                break;
+                return Err(GeneralError::new(format!(
+                    "Unexpected response when fetching {fetch_type}: {output}"
+                )));
             }
         }
         Ok(all_projects)
@@ -303,22 +766,66 @@ impl Gh {
         Ok(())
     }
 
-    /// Save the projects to the specified file
+    /// Save the projects to `output` (`-` for stdout), or to the configured projects file
+    ///
+    /// When `language` is set, only the repos with this primary language are kept.
+    /// With `output`, only that file is written, not the disk usage file.
+    /// With `color`, languages of the summary are colored when stdout is a terminal
     /// # Errors
     /// Fails if unable to write to file
-    pub(crate) fn save_projects(config: &Config, print_json: bool) -> Result<(), GeneralError> {
-        let projects_path = get_config_path!(config, gh, Gh, file_projects, "projects file")?;
-        let projects_path_disk =
-            get_config_path!(config, gh, Gh, file_projects_disk, "projects file")?;
-        if !print_json {
+    pub(crate) fn save_projects(
+        config: &Config,
+        print_json: bool,
+        include_forks: bool,
+        language: Option<&str>,
+        output: Option<&Path>,
+        color: bool,
+        page_size: u8,
+    ) -> Result<(), GeneralError> {
+        // an export never touches the configured files
+        let (projects_path, projects_path_disk) = match output {
+            Some(output) => (output.to_path_buf(), None),
+            None => (
+                get_config_path!(config, gh, Gh, file_projects, "projects file")?,
+                Some(get_config_path!(
+                    config,
+                    gh,
+                    Gh,
+                    file_projects_disk,
+                    "projects file"
+                )?),
+            ),
+        };
+        let is_stdout = projects_path == Path::new("-");
+        let print_json = print_json || is_stdout;
+        let quiet = config.quiet || print_json;
+        let client = GraphqlClient::try_from_config(config)?;
+        if !quiet {
             println!("Saving projects to {}", projects_path.display());
         }
         let debug_level = if print_json { 0 } else { config.debug + 1 };
-        let mut repos = Gh::fetch_projects(&ProjectType::Repos, debug_level)?;
+        let mut repos = Gh::fetch_projects(
+            &ProjectType::Repos,
+            &client,
+            debug_level,
+            include_forks,
+            page_size,
+        )?;
         repos.sort_by(|a, b| a.name.cmp(&b.name));
-        let mut gists = Gh::fetch_projects(&ProjectType::Gists, debug_level)?;
+        let mut gists = if let Some(language) = language {
+            repos.retain(|repo| repo.has_language(language));
+            Vec::new()
+        } else {
+            Gh::fetch_projects(
+                &ProjectType::Gists,
+                &client,
+                debug_level,
+                include_forks,
+                page_size,
+            )?
+        };
         gists.sort_by(|a, b| a.name.cmp(&b.name));
-        if !print_json {
+        if !quiet {
             println!(
                 "Saving {} repos and {} gists to {}",
                 repos.len(),
@@ -331,9 +838,105 @@ impl Gh {
             .map(|p| (p.url.replace("https://", ""), p.disk_usage))
             .collect();
 
-        pretty_print(map, &projects_path_disk)?;
+        if let Some(projects_path_disk) = projects_path_disk {
+            pretty_print(map, &projects_path_disk)?;
+        }
+        let summary = GhProjectsSummary::from_projects(&repos, &gists);
         repos.append(&mut gists);
         pretty_print(repos, &projects_path)?;
+        if is_stdout {
+            return Ok(());
+        }
+        if print_json {
+            println!("{}", serde_json::to_string(&summary)?);
+        } else if !config.quiet {
+            summary.display(color && config.color && std::io::stdout().is_terminal());
+        }
+        Ok(())
+    }
+
+    /// Save the starred repositories to the specified file (`-` for stdout)
+    /// # Errors
+    /// Fails if unable to fetch the stars or to write to file
+    pub(crate) fn save_stars(config: &Config, stars_path: &Path) -> Result<(), GeneralError> {
+        let is_stdout = stars_path == Path::new("-");
+        let debug_level = if is_stdout { 0 } else { config.debug + 1 };
+        let client = GraphqlClient::try_from_config(config)?;
+        let mut stars = Gh::fetch_projects(
+            &ProjectType::Stars,
+            &client,
+            debug_level,
+            true,
+            MAX_PAGE_SIZE,
+        )?;
+        stars.sort_by(|a, b| a.name.cmp(&b.name));
+        pretty_print(&stars, stars_path)?;
+        if !is_stdout && !config.quiet {
+            println!(
+                "Saving {} starred repos to {}",
+                stars.len(),
+                stars_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Save the pulls, the projects and the languages into a single profile file
+    /// # Errors
+    /// Fails if unable to fetch the data or to write to file
+    pub(crate) fn save_profile(config: &Config, profile_path: &Path) -> Result<(), GeneralError> {
+        let is_stdout = profile_path == Path::new("-");
+        let debug_level = if is_stdout { 0 } else { config.debug + 1 };
+        let client = GraphqlClient::try_from_config(config)?;
+        let mut pulls = Vec::new();
+        let mut response_data = GhPageInfo {
+            has_next_page: true,
+            ..Default::default()
+        };
+        while response_data.has_next_page {
+            let pull_requests = Gh::fetch_pulls_page(
+                &response_data.end_cursor,
+                PullsQueryOptions::default(),
+                &client,
+                debug_level.saturating_sub(1),
+            )?;
+            pulls.extend(pull_requests.edges);
+            response_data = pull_requests.page_info;
+        }
+        let mut repos = Gh::fetch_projects(
+            &ProjectType::Repos,
+            &client,
+            debug_level,
+            false,
+            MAX_PAGE_SIZE,
+        )?;
+        repos.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut gists = Gh::fetch_projects(
+            &ProjectType::Gists,
+            &client,
+            debug_level,
+            false,
+            MAX_PAGE_SIZE,
+        )?;
+        gists.sort_by(|a, b| a.name.cmp(&b.name));
+        let languages = GhProjectsSummary::from_projects(&repos, &gists).languages;
+        let profile = GhProfile {
+            username: get_github_username(),
+            pulls: GhPullRequest::merge(Vec::new(), pulls),
+            repos,
+            gists,
+            languages,
+        };
+        pretty_print(&profile, profile_path)?;
+        if !is_stdout && !config.quiet {
+            println!(
+                "Saving profile with {} pulls, {} repos and {} gists to {}",
+                profile.pulls.len(),
+                profile.repos.len(),
+                profile.gists.len(),
+                profile_path.display()
+            );
+        }
         Ok(())
     }
 }
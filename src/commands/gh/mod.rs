@@ -1,5 +1,7 @@
 //! Module for the `gh` subcommand.
 //! See [`crate::commands::gh::lib`] for more information.
 
+pub(crate) mod graphql;
 pub(crate) mod lib;
+pub(crate) mod queries;
 pub(crate) mod types;
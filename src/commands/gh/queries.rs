@@ -0,0 +1,246 @@
+//! Builders of the github GraphQL queries
+//!
+//! The owner is not part of the queries, it is sent as the `$owner` variable
+
+use super::lib::PullsQueryOptions;
+
+/// Maximum number of nodes per GraphQL page, allowed by github
+pub(crate) const MAX_PAGE_SIZE: u8 = 100;
+
+/// Project type
+pub(crate) enum ProjectType {
+    /// Gists
+    Gists,
+    /// Repos
+    Repos,
+    /// Starred repos
+    Stars,
+}
+
+impl ProjectType {
+    /// Name of the user field to query
+    pub(crate) fn field(&self) -> &'static str {
+        match self {
+            Self::Gists => "gists",
+            Self::Repos => "repositories",
+            Self::Stars => "starredRepositories",
+        }
+    }
+
+    /// Arguments of the query
+    fn arguments(&self, include_forks: bool) -> &'static str {
+        match self {
+            Self::Gists => "privacy: PUBLIC",
+            Self::Repos if include_forks => "ownerAffiliations: [OWNER], privacy: PUBLIC",
+            Self::Repos => "isFork: false, ownerAffiliations: [OWNER], privacy: PUBLIC",
+            Self::Stars => "",
+        }
+    }
+
+    /// Additional data to fetch for each node
+    fn data(&self) -> &'static str {
+        match self {
+            Self::Gists => "",
+            Self::Stars => {
+                "owner {
+                        login
+                    }
+                    primaryLanguage {
+                        name
+                        color
+                    }"
+            }
+            Self::Repos => {
+                "primaryLanguage {
+                        name
+                        color
+                    }
+                    archivedAt
+                    isFork
+                    homepageUrl
+                    diskUsage
+                    forkCount
+                    licenseInfo {
+                        name
+                    }"
+            }
+        }
+    }
+}
+
+/// Build the pagination arguments of a GraphQL connection
+///
+/// `extra` arguments, if any, are appended after the page size and cursor
+fn page_arguments(page_size: u8, end_cursor: &str, extra: &str) -> String {
+    let mut arguments = vec![format!("first: {}", page_size.clamp(1, MAX_PAGE_SIZE))];
+    if !end_cursor.trim().is_empty() {
+        arguments.push(format!("after: \"{end_cursor}\""));
+    }
+    if !extra.is_empty() {
+        arguments.push(extra.to_string());
+    }
+    arguments.join(", ")
+}
+
+/// Build the query of a page of pull requests, after `end_cursor` (empty for the first page)
+pub(crate) fn pulls_query(end_cursor: &str, options: PullsQueryOptions) -> String {
+    let order = if options.newest_first {
+        "orderBy: {field: CREATED_AT, direction: DESC}"
+    } else {
+        ""
+    };
+    let arguments = page_arguments(options.page_size, end_cursor, order);
+    let labels = if options.labels {
+        "labels(first: 10) { nodes { name color } }"
+    } else {
+        ""
+    };
+    let languages = options.languages;
+    format!(
+        "
+    query($owner: String!) {{
+        user(login: $owner) {{
+            pullRequests({arguments}) {{
+                edges {{
+                    node {{
+                        id
+                        number
+                        title
+                        url
+                        state
+                        createdAt
+                        mergedAt
+                        closedAt
+                        additions
+                        deletions
+                        changedFiles
+                        {labels}
+                        baseRepository {{
+                            url
+                            name
+                            description
+                            owner {{
+                            login
+                            }}
+                            languages(first: {languages}) {{
+                                nodes {{
+                                    name
+                                    color
+                                }}
+                            }}
+                        }}
+                    }}
+                }}
+                pageInfo {{
+                    endCursor
+                    startCursor
+                    hasNextPage
+                    hasPreviousPage
+                }}
+            }}
+        }}
+    }}"
+    )
+}
+
+/// Build the query of a page of projects, after `end_cursor` (empty for the first page)
+pub(crate) fn projects_query(
+    project_type: &ProjectType,
+    end_cursor: &str,
+    page_size: u8,
+    include_forks: bool,
+) -> String {
+    let field = project_type.field();
+    let arguments = page_arguments(page_size, end_cursor, project_type.arguments(include_forks));
+    let data = project_type.data();
+    format!(
+        "
+    query( $owner: String!){{
+        user(login: $owner) {{
+            {field}({arguments}) {{
+                pageInfo {{
+                    hasNextPage
+                    endCursor
+                    startCursor
+                }}
+                nodes {{
+                    url
+                    name
+                    {data}
+                    description
+                    stargazerCount
+                }}
+            }}
+        }}
+    }}"
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc)]
+mod tests {
+    //! Tests of the generated queries
+
+    use super::{MAX_PAGE_SIZE, ProjectType, projects_query, pulls_query};
+    use crate::commands::gh::lib::PullsQueryOptions;
+
+    /// The first page has no cursor
+    #[test]
+    fn pulls_first_page() {
+        let query = pulls_query("", PullsQueryOptions::default());
+        assert!(query.contains("pullRequests(first: 100)"));
+        assert!(!query.contains("after:"));
+        assert!(!query.contains("labels(first: 10)"));
+        assert!(query.contains("languages(first: 1)"));
+    }
+
+    /// The following pages start after the cursor, the order comes last
+    #[test]
+    fn pulls_after_cursor() {
+        let options = PullsQueryOptions {
+            newest_first: true,
+            labels: true,
+            ..PullsQueryOptions::default()
+        };
+        let query = pulls_query("Y3Vyc29y", options);
+        assert!(query.contains(
+            "pullRequests(first: 100, after: \"Y3Vyc29y\", orderBy: {field: CREATED_AT, direction: DESC})"
+        ));
+        assert!(query.contains("labels(first: 10)"));
+    }
+
+    /// The page size is clamped between 1 and `MAX_PAGE_SIZE`
+    #[test]
+    fn page_size_limit() {
+        let options = |page_size| PullsQueryOptions {
+            page_size,
+            ..PullsQueryOptions::default()
+        };
+        assert!(pulls_query("", options(20)).contains("pullRequests(first: 20)"));
+        assert!(pulls_query("", options(0)).contains("pullRequests(first: 1)"));
+        assert!(
+            pulls_query("", options(u8::MAX))
+                .contains(&format!("pullRequests(first: {MAX_PAGE_SIZE})"))
+        );
+        assert!(
+            projects_query(&ProjectType::Gists, "", u8::MAX, false)
+                .contains(&format!("gists(first: {MAX_PAGE_SIZE}, privacy: PUBLIC)"))
+        );
+    }
+
+    /// Projects queries put the cursor before the type arguments
+    #[test]
+    fn projects_pages() {
+        let first = projects_query(&ProjectType::Repos, "", MAX_PAGE_SIZE, false);
+        assert!(first.contains(
+            "repositories(first: 100, isFork: false, ownerAffiliations: [OWNER], privacy: PUBLIC)"
+        ));
+        assert!(first.contains("diskUsage"));
+        let next = projects_query(&ProjectType::Repos, "abc", 50, true);
+        assert!(next.contains(
+            "repositories(first: 50, after: \"abc\", ownerAffiliations: [OWNER], privacy: PUBLIC)"
+        ));
+        let stars = projects_query(&ProjectType::Stars, "abc", 10, false);
+        assert!(stars.contains("starredRepositories(first: 10, after: \"abc\")"));
+    }
+}
@@ -1,6 +1,10 @@
 //! Type for github graphql api
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
 
+use crate::utils::paint_hex;
+
 /// Github Response
 #[derive(Deserialize, Default, Debug)]
 pub struct GhResponse {
@@ -53,6 +57,62 @@ pub struct GhPullRequest {
     node: GhPullRequestNode,
 }
 
+impl GhPullRequest {
+    /// Date used to know which version of a pull is the most recent
+    fn updated_at(&self) -> &str {
+        self.node
+            .merged_at
+            .as_deref()
+            .or(self.node.closed_at.as_deref())
+            .unwrap_or(&self.node.created_at)
+    }
+
+    /// Base repository of the pull, as `owner/name`
+    pub fn base_repository_full_name(&self) -> String {
+        let repo = &self.node.base_repository;
+        format!("{}/{}", repo.owner.login, repo.name)
+    }
+
+    /// Creation date of the pull
+    pub fn created_at(&self) -> &str {
+        &self.node.created_at
+    }
+
+    /// State of the pull (`OPEN`, `CLOSED` or `MERGED`)
+    pub fn state(&self) -> &str {
+        &self.node.state
+    }
+
+    /// Merge two lists of pulls, deduplicating them
+    ///
+    /// Pulls are keyed by node id, falling back to the (case-insensitive) url.
+    /// When a pull is present twice, the most recent version is kept
+    pub fn merge(pulls: Vec<GhPullRequest>, new_pulls: Vec<GhPullRequest>) -> Vec<GhPullRequest> {
+        let mut merged: Vec<GhPullRequest> = Vec::with_capacity(pulls.len() + new_pulls.len());
+        let mut by_id: HashMap<String, usize> = HashMap::new();
+        let mut by_url: HashMap<String, usize> = HashMap::new();
+        for pull in pulls.into_iter().chain(new_pulls) {
+            let url = pull.node.url.to_lowercase();
+            let found = by_id
+                .get(&pull.node.id)
+                .or_else(|| by_url.get(&url))
+                .copied();
+            if let Some(idx) = found {
+                if pull.updated_at() >= merged[idx].updated_at() {
+                    by_id.insert(pull.node.id.clone(), idx);
+                    by_url.insert(url, idx);
+                    merged[idx] = pull;
+                }
+            } else {
+                by_id.insert(pull.node.id.clone(), merged.len());
+                by_url.insert(url, merged.len());
+                merged.push(pull);
+            }
+        }
+        merged
+    }
+}
+
 /// Pull request node
 #[derive(Deserialize, Serialize, Default, Debug)]
 pub struct GhPullRequestNode {
@@ -75,11 +135,52 @@ pub struct GhPullRequestNode {
     #[serde(rename = "createdAt")]
     created_at: String,
 
+    /// Merged at
+    #[serde(rename = "mergedAt", default)]
+    merged_at: Option<String>,
+
+    /// Closed at
+    #[serde(rename = "closedAt", default)]
+    closed_at: Option<String>,
+
+    /// Number of added lines
+    #[serde(default)]
+    additions: Option<u64>,
+
+    /// Number of deleted lines
+    #[serde(default)]
+    deletions: Option<u64>,
+
+    /// Number of changed files
+    #[serde(rename = "changedFiles", default)]
+    changed_files: Option<u64>,
+
+    /// Labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    labels: Option<GhLabels>,
+
     /// Base repository
     #[serde(rename = "baseRepository")]
     base_repository: GhBaseRepository,
 }
 
+/// Labels
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct GhLabels {
+    /// Nodes
+    nodes: Vec<GhLabel>,
+}
+
+/// Label
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct GhLabel {
+    /// Name of the label
+    name: String,
+
+    /// Color of the label
+    color: String,
+}
+
 /// Base repository
 #[derive(Deserialize, Serialize, Default, Debug)]
 pub struct GhBaseRepository {
@@ -117,10 +218,10 @@ pub struct GhLanguages {
 #[derive(Deserialize, Serialize, Default, Debug)]
 pub struct GhLanguage {
     /// Name of the language
-    name: String,
+    pub name: String,
 
     /// Color of the language
-    color: Option<String>,
+    pub color: Option<String>,
 }
 
 /// License
@@ -142,6 +243,10 @@ pub struct GhProject {
     /// Project description
     pub description: Option<String>,
 
+    /// Owner, only fetched for starred repositories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<GhOwner>,
+
     /// stargazerCount
     #[serde(rename = "stargazerCount")]
     pub stargazer_count: i32,
@@ -150,6 +255,10 @@ pub struct GhProject {
     #[serde(rename = "archivedAt")]
     pub archived_at: Option<String>,
 
+    /// isFork
+    #[serde(rename = "isFork")]
+    pub is_fork: Option<bool>,
+
     /// homepageUrl
     #[serde(rename = "homepageUrl")]
     pub homepage_url: Option<String>,
@@ -171,3 +280,101 @@ pub struct GhProject {
     #[serde(rename = "primaryLanguage")]
     pub primary_language: Option<GhLanguage>,
 }
+
+impl GhProject {
+    /// Whether the primary language of the project is `language`, ignoring case
+    pub fn has_language(&self, language: &str) -> bool {
+        self.primary_language
+            .as_ref()
+            .is_some_and(|primary| primary.name.eq_ignore_ascii_case(language))
+    }
+}
+
+/// Aggregate stats of projects
+#[derive(Serialize, Debug)]
+pub struct GhProjectsSummary {
+    /// Number of repositories
+    pub repos: usize,
+
+    /// Number of gists
+    pub gists: usize,
+
+    /// Total stargazers of the repositories
+    pub stargazers: i64,
+
+    /// Total disk usage of the repositories (in kilobytes)
+    pub disk_usage: u64,
+
+    /// Number of repositories per primary language
+    pub languages: BTreeMap<String, usize>,
+
+    /// Github color of each language
+    #[serde(skip)]
+    pub language_colors: BTreeMap<String, String>,
+}
+
+impl GhProjectsSummary {
+    /// Compute the summary of repositories and gists
+    pub fn from_projects(repos: &[GhProject], gists: &[GhProject]) -> Self {
+        let mut languages = BTreeMap::new();
+        let mut language_colors = BTreeMap::new();
+        for language in repos
+            .iter()
+            .filter_map(|repo| repo.primary_language.as_ref())
+        {
+            *languages.entry(language.name.clone()).or_insert(0) += 1;
+            if let Some(color) = &language.color {
+                language_colors.insert(language.name.clone(), color.clone());
+            }
+        }
+        Self {
+            repos: repos.len(),
+            gists: gists.len(),
+            stargazers: repos
+                .iter()
+                .map(|repo| i64::from(repo.stargazer_count))
+                .sum(),
+            disk_usage: repos.iter().filter_map(|repo| repo.disk_usage).sum(),
+            languages,
+            language_colors,
+        }
+    }
+
+    /// Display the summary
+    ///
+    /// With `color`, language names are printed in their github color,
+    /// followed by the hex code as a legend
+    pub fn display(&self, color: bool) {
+        println!("Repos: {}, gists: {}", self.repos, self.gists);
+        println!("Total stargazers: {}", self.stargazers);
+        println!("Total disk usage: {} KB", self.disk_usage);
+        for (language, count) in &self.languages {
+            let hex = self.language_colors.get(language);
+            match hex.and_then(|hex| paint_hex(language, hex)) {
+                Some(painted) if color => {
+                    println!("  {painted} ({}): {count}", hex.map_or("", String::as_str));
+                }
+                _ => println!("  {language}: {count}"),
+            }
+        }
+    }
+}
+
+/// Github profile, aggregating pulls, projects and languages
+#[derive(Serialize, Debug)]
+pub struct GhProfile {
+    /// Github username
+    pub username: String,
+
+    /// Pull requests
+    pub pulls: Vec<GhPullRequest>,
+
+    /// Repositories
+    pub repos: Vec<GhProject>,
+
+    /// Gists
+    pub gists: Vec<GhProject>,
+
+    /// Number of repositories per primary language
+    pub languages: BTreeMap<String, usize>,
+}
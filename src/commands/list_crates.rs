@@ -1,9 +1,17 @@
 //! [`list_crates`] function
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Write, fs, path::PathBuf, thread, time::Duration, vec};
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+    vec,
+};
 
 use crate::{
     commands::gh::lib::get_github_username,
@@ -17,9 +25,23 @@ fn get_user_agent() -> String {
     "n4n5 (https://github.com/Its-Just-Nans/n4n5)".to_string()
 }
 
+/// Sort key of crates
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum CrateSort {
+    /// Sort by name
+    Name,
+
+    /// Sort by total downloads, most downloaded first
+    Downloads,
+
+    /// Sort by recent downloads, most downloaded first
+    Recent,
+}
+
 /// A simple CLI example
 #[derive(Parser, Debug, Clone)]
 #[command(name = "list_crates")]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ListCrates {
     /// Specify username
     #[arg(long, default_value_t = get_github_username())]
@@ -36,6 +58,10 @@ pub struct ListCrates {
     #[arg(long)]
     output_list: Option<PathBuf>,
 
+    /// Output csv
+    #[arg(long)]
+    output_csv: Option<PathBuf>,
+
     /// Output list long/full
     #[arg(long)]
     output_list_full: Option<PathBuf>,
@@ -44,6 +70,26 @@ pub struct ListCrates {
     #[arg(long, default_value_t = 500)]
     delay: u64,
 
+    /// Number of crates fetched in parallel, each worker respecting the delay
+    #[arg(short = 'J', long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..))]
+    jobs: u8,
+
+    /// Connect and request timeout (in seconds)
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+
+    /// Number of retries of a transient failure, like a timeout or a 5xx (0 to disable)
+    #[arg(long, default_value_t = 2)]
+    retries: u32,
+
+    /// Ignore cached crates.io responses (they are still refreshed)
+    #[arg(long, default_value_t = false)]
+    no_cache: bool,
+
+    /// Time to live of cached crates.io responses (in seconds)
+    #[arg(long, default_value_t = 86400)]
+    cache_ttl: u64,
+
     /// Filter crates
     #[arg(long)]
     filtered: Option<String>,
@@ -55,19 +101,51 @@ pub struct ListCrates {
     /// Specials crates
     #[arg(long)]
     specials: Option<String>,
+
+    /// Only fetch full info for the first n crates
+    #[arg(long)]
+    sample: Option<usize>,
+
+    /// Sort the full crates infos, instead of keeping the crates.io order
+    #[arg(long, value_enum)]
+    sort: Option<CrateSort>,
+
+    /// Print aggregate stats of the crates
+    #[arg(long, default_value_t = false)]
+    summary: bool,
+
+    /// Resume from the full list file, only fetching missing crates
+    #[arg(long, default_value_t = false)]
+    resume: bool,
+
+    /// Skip crates without description in markdown
+    #[arg(long, default_value_t = false)]
+    require_description: bool,
+
+    /// Print the summary as json
+    #[arg(short = 'j', long = "json", default_value_t = false)]
+    print_json: bool,
+
+    /// Keep yanked versions when computing the max version
+    #[arg(long, default_value_t = false)]
+    include_yanked: bool,
 }
 
 impl ListCrates {
     /// Get all crates name
     /// # Errors
     /// Error if request fails
-    pub fn get_all_crates(&self, verbose: bool, delay: u64) -> Result<Vec<String>, GeneralError> {
-        let client = Client::builder().user_agent(&self.user_agent).build()?;
+    pub fn get_all_crates(
+        &self,
+        client: &Client,
+        verbose: bool,
+        delay: u64,
+    ) -> Result<Vec<String>, GeneralError> {
         let per_page: usize = 50;
 
         // Step 1: Fetch user ID
         let user_url = format!("https://crates.io/api/v1/users/{}", self.username);
-        let user_res: UserResponse = client.get(&user_url).send()?.json()?;
+        let user_res: UserResponse = serde_json::from_str(&self.get_text(client, &user_url)?)?;
 
         let Some(user_id) = user_res.user else {
             let msg = format!("User '{}' not found on crates.io.", self.username);
@@ -90,7 +168,7 @@ impl ListCrates {
                 user_id.id, page, per_page
             );
 
-            let resp: CrateResponse = client.get(&url).send()?.json()?;
+            let resp: CrateResponse = serde_json::from_str(&self.get_text(client, &url)?)?;
 
             if resp.crates.is_empty() {
                 break;
@@ -119,7 +197,7 @@ impl ListCrates {
     /// Error if fails to convert to string
     pub fn generate_markdown_table<I>(&self, rows: I) -> Result<String, GeneralError>
     where
-        I: Iterator<Item = [String; 4]>,
+        I: Iterator<Item = [String; 6]>,
     {
         let specials_crates = if let Some(spe) = &self.specials {
             spe.split(',').map(|s| s.trim().to_string()).collect()
@@ -130,6 +208,8 @@ impl ListCrates {
             "Crate".to_string(),
             "Description".to_string(),
             "Homepage && Repo".to_string(),
+            "Downloads".to_string(),
+            "Version".to_string(),
         ]
         .to_vec()];
         let (mut table1, mut table2, mut table3) = (Vec::new(), Vec::new(), Vec::new());
@@ -150,7 +230,7 @@ impl ListCrates {
         }
         let mut buf = String::new();
         let table1 = header.clone().into_iter().chain(table1);
-        let table1_markdown = table_to_markdown_table(table1, 3)?;
+        let table1_markdown = table_to_markdown_table(table1, 5)?;
         write!(&mut buf, "{table1_markdown}")?;
         if !table2.is_empty() {
             if let Some(pattern) = &self.filtered {
@@ -165,13 +245,13 @@ impl ListCrates {
                 writeln!(&mut buf, "\n## Filtered\n")?;
             }
             let table2 = header.clone().into_iter().chain(table2);
-            let table2_markdown = table_to_markdown_table(table2, 3)?;
+            let table2_markdown = table_to_markdown_table(table2, 5)?;
             write!(&mut buf, "{table2_markdown}")?;
         }
         if !table3.is_empty() {
             writeln!(&mut buf, "\n## Others\n")?;
             let table3 = header.into_iter().chain(table3);
-            let table3_markdown = table_to_markdown_table(table3, 3)?;
+            let table3_markdown = table_to_markdown_table(table3, 5)?;
             write!(&mut buf, "{table3_markdown}")?;
         }
         Ok(buf)
@@ -180,65 +260,205 @@ impl ListCrates {
     /// Get the music file path
     /// # Errors
     /// Fails if the file cannot be found
-    pub fn list_crates(&self, _config: &mut Config) -> Result<(), GeneralError> {
-        let all_crates = self.get_all_crates(self.verbose, self.delay)?;
+    pub fn list_crates(&self, config: &mut Config) -> Result<(), GeneralError> {
+        let client = Client::builder()
+            .user_agent(&self.user_agent)
+            .connect_timeout(Duration::from_secs(self.timeout))
+            .timeout(Duration::from_secs(self.timeout))
+            .build()?;
+        let all_crates = self.get_all_crates(&client, self.verbose, self.delay)?;
         if let Some(list_file) = &self.output_list {
             pretty_print(&all_crates, list_file)?;
         }
-        if self.output_list_full.is_none() && self.output_markdown.is_none() {
+        if self.output_list_full.is_none()
+            && self.output_markdown.is_none()
+            && self.output_csv.is_none()
+            && !self.summary
+        {
             return Ok(());
         }
-        let all_crates_infos: Vec<CrateData> = all_crates
-            .iter()
-            .map(|crate_name| Self::get_one_crate(crate_name, self.delay))
-            .filter_map(|res| match res {
-                Ok(val) => {
-                    if self.verbose {
-                        println!("Fetched {}", val.krate.name);
-                    }
-                    Some(val)
-                }
-                Err(err) => {
-                    eprintln!("Error fetching crate: {err}");
-                    None
-                }
-            })
-            .collect();
+        if let Some(sample) = self.sample
+            && sample < all_crates.len()
+        {
+            eprintln!(
+                "Note: output is a sample of {sample} out of {} crates",
+                all_crates.len()
+            );
+        }
+        // the sample is taken after sorting, only the downloads need the full infos first
+        let mut crates_to_fetch = all_crates;
+        if let (Some(sample), None | Some(CrateSort::Name)) = (self.sample, self.sort) {
+            if self.sort.is_some() {
+                crates_to_fetch.sort_by_key(|name| name.to_lowercase());
+            }
+            crates_to_fetch.truncate(sample);
+        }
+        let cache_dir = self.cache_dir();
+        let all_crates_infos =
+            self.fetch_crates_infos(&client, &crates_to_fetch, cache_dir.as_deref())?;
+        let all_crates_infos = sort_and_sample(all_crates_infos, self.sort, self.sample);
         if let Some(file_list_full) = &self.output_list_full {
             pretty_print(&all_crates_infos, file_list_full)?;
         }
-        let Some(file_markdown) = &self.output_markdown else {
-            return Ok(());
-        };
-        let rows = all_crates_infos.into_iter().map(|one_crate| {
-            let CrateInnerData {
-                description,
-                name,
-                repository,
-                homepage,
-                documentation,
-                ..
-            } = one_crate.krate;
-            let name_with_url = format!("[{name}](https://crates.io/crates/{name})");
-            let desc = description.unwrap_or("N/A".to_string());
-            let homepage = if let Some(h) = homepage {
-                &format!("<{h}>")
-            } else {
-                "N/A"
-            };
-            let url = if let Some(repo) = repository {
-                &format!("<{repo}>")
-            } else {
-                "N/A"
-            };
-            let docs = if let Some(doc) = documentation {
-                &format!("<{doc}>")
+        if self.summary {
+            let summary = CratesSummary::from_crates(&all_crates_infos);
+            if self.print_json {
+                println!("{}", serde_json::to_string(&summary)?);
             } else {
-                "N/A"
+                summary.display();
+            }
+        }
+        if let Some(file_csv) = &self.output_csv {
+            write_csv(&all_crates_infos, file_csv, config.quiet)?;
+        }
+        if let Some(file_markdown) = &self.output_markdown {
+            self.write_markdown(all_crates_infos, file_markdown, config.quiet)?;
+        }
+        Ok(())
+    }
+
+    /// Fetch the full info of crates
+    ///
+    /// When writing the full list to a file, it is updated after each crate,
+    /// so that `--resume` can skip the crates already fetched
+    /// # Errors
+    /// Fails if the full list can't be read or written
+    fn fetch_crates_infos(
+        &self,
+        client: &Client,
+        crates_to_fetch: &[String],
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<CrateData>, GeneralError> {
+        let incremental_file = self
+            .output_list_full
+            .as_deref()
+            .filter(|path| *path != Path::new("-"));
+        let mut all_crates_infos: Vec<CrateData> = Vec::new();
+        if self.resume {
+            let Some(file_list_full) = incremental_file else {
+                return Err(GeneralError::new(
+                    "--resume requires --output-list-full with a file path",
+                ));
             };
-            let infos = format!("{homepage} <br/> {url} <br/> {docs}");
-            [name, name_with_url, desc, infos]
+            if file_list_full.is_file() {
+                let content = fs::read_to_string(file_list_full)?;
+                if !content.trim().is_empty() {
+                    all_crates_infos = serde_json::from_str(&content)?;
+                }
+            }
+            if self.verbose {
+                println!("Resuming with {} crates", all_crates_infos.len());
+            }
+        }
+        let missing_crates: Vec<&String> = crates_to_fetch
+            .iter()
+            .filter(|crate_name| {
+                !all_crates_infos
+                    .iter()
+                    .any(|one_crate| &one_crate.krate.name == *crate_name)
+            })
+            .collect();
+        let jobs = usize::from(self.jobs);
+        thread::scope(|scope| -> Result<(), GeneralError> {
+            let (sender, receiver) = mpsc::channel();
+            for worker in 0..jobs {
+                let sender = sender.clone();
+                let missing_crates = &missing_crates;
+                scope.spawn(move || {
+                    for crate_name in missing_crates.iter().skip(worker).step_by(jobs) {
+                        let result = self.get_one_crate(client, crate_name, cache_dir);
+                        if sender.send(result).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+            for result in receiver {
+                match result {
+                    Ok(val) => {
+                        if self.verbose {
+                            println!("Fetched {}", val.krate.name);
+                        }
+                        all_crates_infos.push(val);
+                        if let Some(file_list_full) = incremental_file {
+                            pretty_print(&all_crates_infos, file_list_full)?;
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("Error fetching crate: {err}");
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        // keep the order of the crates list
+        all_crates_infos.sort_by_key(|one_crate| {
+            crates_to_fetch
+                .iter()
+                .position(|name| name == &one_crate.krate.name)
+                .unwrap_or(usize::MAX)
         });
+        Ok(all_crates_infos)
+    }
+
+    /// Render the crates as markdown and write them to a file (`-` for stdout)
+    /// # Errors
+    /// Fails if the markdown can't be generated or written
+    fn write_markdown(
+        &self,
+        all_crates_infos: Vec<CrateData>,
+        file_markdown: &Path,
+        quiet: bool,
+    ) -> Result<(), GeneralError> {
+        let require_description = self.require_description;
+        let rows = all_crates_infos
+            .into_iter()
+            .filter(|one_crate| {
+                !require_description
+                    || one_crate
+                        .krate
+                        .description
+                        .as_ref()
+                        .is_some_and(|desc| !desc.trim().is_empty())
+            })
+            .map(|one_crate| {
+                let CrateInnerData {
+                    description,
+                    name,
+                    repository,
+                    homepage,
+                    documentation,
+                    downloads,
+                    recent_downloads,
+                    max_version,
+                    newest_version,
+                } = one_crate.krate;
+                let name_with_url = format!("[{name}](https://crates.io/crates/{name})");
+                let desc = description.unwrap_or("N/A".to_string());
+                let homepage = if let Some(h) = homepage {
+                    &format!("<{h}>")
+                } else {
+                    "N/A"
+                };
+                let url = if let Some(repo) = repository {
+                    &format!("<{repo}>")
+                } else {
+                    "N/A"
+                };
+                let docs = if let Some(doc) = documentation {
+                    &format!("<{doc}>")
+                } else {
+                    "N/A"
+                };
+                let infos = format!("{homepage} <br/> {url} <br/> {docs}");
+                let downloads = match recent_downloads {
+                    Some(recent) => format!("{downloads} ({recent} recent)"),
+                    None => downloads.to_string(),
+                };
+                let version = max_version.or(newest_version).unwrap_or("N/A".to_string());
+                [name, name_with_url, desc, infos, downloads, version]
+            });
         let tables = self.generate_markdown_table(rows)?;
         let mut buf = String::new();
         writeln!(&mut buf, "# crates")?;
@@ -250,35 +470,186 @@ impl ListCrates {
         writeln!(&mut buf)?;
         write!(&mut buf, "{tables}")?;
 
-        if file_markdown == &PathBuf::from("-") {
+        if file_markdown == Path::new("-") {
             print!("{buf}");
             return Ok(());
         }
         fs::write(file_markdown, buf)?;
-        println!("Written to {}", file_markdown.display());
+        if !quiet {
+            println!("Written to {}", file_markdown.display());
+        }
         Ok(())
     }
 
     /// Get info for one crate
+    ///
+    /// When `cache_dir` is set, a cached response younger than `--cache-ttl`
+    /// is used instead of the network, and fresh responses are cached
     /// # Errors
     /// Error if request fails or serde fails
-    pub fn get_one_crate(crate_name: &String, delay: u64) -> Result<CrateData, GeneralError> {
-        // Sleep 0.5 seconds to avoid rate limiting
-        thread::sleep(Duration::from_millis(delay));
-        let user_agent = get_user_agent();
+    pub fn get_one_crate(
+        &self,
+        client: &Client,
+        crate_name: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<CrateData, GeneralError> {
+        let cache_file = cache_dir.map(|dir| dir.join(format!("{crate_name}.json")));
+        if !self.no_cache
+            && let Some(cache_file) = &cache_file
+            && let Some(mut crate_data) = self.read_cache(cache_file)
+        {
+            if !self.include_yanked {
+                crate_data.skip_yanked_max_version();
+            }
+            return Ok(crate_data);
+        }
+        // Sleep to avoid rate limiting
+        thread::sleep(Duration::from_millis(self.delay));
         let url = format!("https://crates.io/api/v1/crates/{crate_name}");
-        let client = Client::new();
+        let response = self.get_text(client, &url)?;
 
-        let response = client
-            .get(&url)
-            .header("User-Agent", user_agent)
-            .send()?
-            .error_for_status()?
-            .text()?;
-
-        let crate_data: CrateData = serde_json::from_str(&response)?;
+        let mut crate_data: CrateData = serde_json::from_str(&response)?;
+        if let Some(cache_file) = &cache_file
+            && let Err(err) = fs::write(cache_file, &response)
+        {
+            eprintln!("Unable to write cache '{}': {err}", cache_file.display());
+        }
+        if !self.include_yanked {
+            crate_data.skip_yanked_max_version();
+        }
         Ok(crate_data)
     }
+
+    /// Get the crates cache directory, `None` when it can't be used
+    ///
+    /// With `--no-cache`, an existing directory is still refreshed but never created.
+    /// Cache errors are reported and the cache is skipped
+    fn cache_dir(&self) -> Option<PathBuf> {
+        let cache_dir = match Config::get_cache_dir("crates") {
+            Ok(cache_dir) => cache_dir,
+            Err(err) => {
+                eprintln!("Cache disabled: {err}");
+                return None;
+            }
+        };
+        if self.no_cache {
+            return cache_dir.is_dir().then_some(cache_dir);
+        }
+        match fs::create_dir_all(&cache_dir) {
+            Ok(()) => Some(cache_dir),
+            Err(err) => {
+                eprintln!(
+                    "Cache disabled, unable to create '{}': {err}",
+                    cache_dir.display()
+                );
+                None
+            }
+        }
+    }
+
+    /// Read a cached crates.io response, if it is still fresh
+    fn read_cache(&self, cache_file: &Path) -> Option<CrateData> {
+        let age = fs::metadata(cache_file)
+            .ok()?
+            .modified()
+            .ok()?
+            .elapsed()
+            .ok()?;
+        if age > Duration::from_secs(self.cache_ttl) {
+            return None;
+        }
+        let content = fs::read_to_string(cache_file).ok()?;
+        let crate_data = serde_json::from_str(&content).ok()?;
+        if self.verbose {
+            println!("Using cache {}", cache_file.display());
+        }
+        Some(crate_data)
+    }
+
+    /// Get the body of an url, retrying transient failures up to `retries` times
+    /// # Errors
+    /// Error if the request still fails after all retries
+    fn get_text(&self, client: &Client, url: &str) -> Result<String, GeneralError> {
+        let mut attempt = 0;
+        loop {
+            let response = client
+                .get(url)
+                .send()
+                .and_then(reqwest::blocking::Response::error_for_status)
+                .and_then(reqwest::blocking::Response::text);
+            match response {
+                Ok(body) => return Ok(body),
+                Err(err) if attempt < self.retries && is_transient(&err) => {
+                    attempt += 1;
+                    if self.verbose {
+                        eprintln!(
+                            "Request to {url} failed ({err}), retry {attempt}/{}",
+                            self.retries
+                        );
+                    }
+                    thread::sleep(Duration::from_millis(self.delay));
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+/// Whether a failed request is worth retrying
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err.status().is_some_and(|status| {
+            status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        })
+}
+
+/// Quote a csv field if needed
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Write the crates as csv to a file (`-` for stdout)
+/// # Errors
+/// Fails if the csv can't be written
+fn write_csv(
+    all_crates_infos: &[CrateData],
+    file_csv: &Path,
+    quiet: bool,
+) -> Result<(), GeneralError> {
+    let mut buf = String::new();
+    writeln!(
+        &mut buf,
+        "name,description,repository,homepage,documentation"
+    )?;
+    for one_crate in all_crates_infos {
+        let krate = &one_crate.krate;
+        let line = [
+            Some(&krate.name),
+            krate.description.as_ref(),
+            krate.repository.as_ref(),
+            krate.homepage.as_ref(),
+            krate.documentation.as_ref(),
+        ]
+        .iter()
+        .map(|value| csv_field(value.map_or("", |v| v.as_str())))
+        .collect::<Vec<_>>()
+        .join(",");
+        writeln!(&mut buf, "{line}")?;
+    }
+    if file_csv == Path::new("-") {
+        print!("{buf}");
+        return Ok(());
+    }
+    fs::write(file_csv, buf)?;
+    if !quiet {
+        println!("Written to {}", file_csv.display());
+    }
+    Ok(())
 }
 
 /// User Response
@@ -309,12 +680,91 @@ pub(crate) struct CrateInfo {
     pub id: String,
 }
 
+/// Sort the crates infos by `sort`, then keep the first `sample` ones
+fn sort_and_sample(
+    mut crates_infos: Vec<CrateData>,
+    sort: Option<CrateSort>,
+    sample: Option<usize>,
+) -> Vec<CrateData> {
+    match sort {
+        Some(CrateSort::Name) => crates_infos.sort_by(|a, b| {
+            a.krate
+                .name
+                .to_lowercase()
+                .cmp(&b.krate.name.to_lowercase())
+        }),
+        Some(CrateSort::Downloads) => {
+            crates_infos.sort_by_key(|c| std::cmp::Reverse(c.krate.downloads));
+        }
+        Some(CrateSort::Recent) => {
+            crates_infos.sort_by_key(|c| std::cmp::Reverse(c.krate.recent_downloads));
+        }
+        None => {}
+    }
+    if let Some(sample) = sample {
+        crates_infos.truncate(sample);
+    }
+    crates_infos
+}
+
 /// crate data from crates.io
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CrateData {
     /// inner crate data
     #[serde(rename = "crate")]
     pub krate: CrateInnerData,
+    /// published versions, only read from crates.io responses
+    #[serde(default, skip_serializing)]
+    pub versions: Vec<CrateVersion>,
+}
+
+impl CrateData {
+    /// Set the max version to the highest version that is not yanked
+    ///
+    /// Kept as is when the versions are unknown
+    fn skip_yanked_max_version(&mut self) {
+        if self.versions.is_empty() {
+            return;
+        }
+        self.krate.max_version = self
+            .versions
+            .iter()
+            .filter(|version| !version.yanked)
+            .max_by(|a, b| compare_versions(&a.num, &b.num))
+            .map(|version| version.num.clone());
+    }
+}
+
+/// One published version of a crate
+#[derive(Deserialize, Debug, Clone)]
+pub struct CrateVersion {
+    /// version number
+    pub num: String,
+    /// whether the version is yanked
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// Compare two semver versions, a pre-release being lower than its release
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    /// Split a version into its numeric parts and its pre-release
+    fn parse(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.split('+').next().unwrap_or_default();
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let numbers = core.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+        (numbers, pre)
+    }
+    let (a_numbers, a_pre) = parse(a);
+    let (b_numbers, b_pre) = parse(b);
+    a_numbers.cmp(&b_numbers).then(match (a_pre, b_pre) {
+        (None, None) => std::cmp::Ordering::Equal,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (Some(a_pre), Some(b_pre)) => a_pre.cmp(b_pre),
+    })
 }
 
 /// Crate inner data
@@ -330,4 +780,113 @@ pub struct CrateInnerData {
     pub documentation: Option<String>,
     /// description
     pub description: Option<String>,
+    /// total downloads
+    #[serde(default)]
+    pub downloads: u64,
+    /// downloads in the last 90 days
+    #[serde(default)]
+    pub recent_downloads: Option<u64>,
+    /// highest version
+    #[serde(default)]
+    pub max_version: Option<String>,
+    /// most recently published version
+    #[serde(default)]
+    pub newest_version: Option<String>,
+}
+
+/// Aggregate stats of crates
+#[derive(Serialize, Debug)]
+pub struct CratesSummary {
+    /// number of crates
+    pub crates: usize,
+    /// total downloads
+    pub downloads: u64,
+    /// most downloaded crate
+    pub most_downloaded: Option<String>,
+    /// number of crates without description
+    pub missing_description: usize,
+    /// number of crates without repository
+    pub missing_repository: usize,
+}
+
+impl CratesSummary {
+    /// Compute the summary of a list of crates
+    pub fn from_crates(crates: &[CrateData]) -> Self {
+        let is_missing =
+            |value: &Option<String>| value.as_ref().is_none_or(|v| v.trim().is_empty());
+        Self {
+            crates: crates.len(),
+            downloads: crates.iter().map(|c| c.krate.downloads).sum(),
+            most_downloaded: crates
+                .iter()
+                .max_by_key(|c| c.krate.downloads)
+                .map(|c| c.krate.name.clone()),
+            missing_description: crates
+                .iter()
+                .filter(|c| is_missing(&c.krate.description))
+                .count(),
+            missing_repository: crates
+                .iter()
+                .filter(|c| is_missing(&c.krate.repository))
+                .count(),
+        }
+    }
+
+    /// Display the summary
+    pub fn display(&self) {
+        println!("Number of crates: {}", self.crates);
+        println!("Total downloads: {}", self.downloads);
+        println!(
+            "Most downloaded: {}",
+            self.most_downloaded.as_deref().unwrap_or("N/A")
+        );
+        println!("Missing description: {}", self.missing_description);
+        println!("Missing repository: {}", self.missing_repository);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+mod tests {
+    //! Tests of the crates listing
+
+    use super::{CrateData, CrateSort, sort_and_sample};
+
+    /// Build crates infos from `(name, downloads)` pairs
+    fn crates(data: &[(&str, u64)]) -> Result<Vec<CrateData>, serde_json::Error> {
+        data.iter()
+            .map(|(name, downloads)| {
+                serde_json::from_value(serde_json::json!({
+                    "crate": {"name": name, "downloads": downloads}
+                }))
+            })
+            .collect()
+    }
+
+    /// Names of the crates, in order
+    fn names(crates: &[CrateData]) -> Vec<&str> {
+        crates.iter().map(|c| c.krate.name.as_str()).collect()
+    }
+
+    /// The sample keeps the top crates of the sort, not arbitrary ones
+    #[test]
+    fn sample_after_sort() -> Result<(), serde_json::Error> {
+        let infos = crates(&[("a", 10), ("b", 300), ("c", 20), ("d", 200)])?;
+        let sampled = sort_and_sample(infos, Some(CrateSort::Downloads), Some(2));
+        assert_eq!(names(&sampled), ["b", "d"]);
+        Ok(())
+    }
+
+    /// Without a sort the crates.io order is kept, names sort case-insensitively
+    #[test]
+    fn sample_order_and_name_sort() -> Result<(), serde_json::Error> {
+        let infos = crates(&[("b", 1), ("a", 2), ("c", 3)])?;
+        assert_eq!(names(&sort_and_sample(infos, None, Some(2))), ["b", "a"]);
+        let infos = crates(&[("B", 1), ("a", 2), ("c", 3)])?;
+        assert_eq!(
+            names(&sort_and_sample(infos, Some(CrateSort::Name), None)),
+            ["a", "B", "c"]
+        );
+        Ok(())
+    }
 }
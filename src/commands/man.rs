@@ -2,8 +2,7 @@
 
 use clap::CommandFactory;
 use clap_mangen::generate_to as man_generate_to;
-use home::home_dir;
-use std::fs::create_dir_all;
+use std::path::PathBuf;
 
 use crate::cli::CliArgs;
 use crate::commands::Commands;
@@ -11,21 +10,21 @@ use crate::config::Config;
 use crate::errors::GeneralError;
 
 impl Commands {
-    /// generate man page
+    /// generate man page in `custom_dir`, or in the config dir
     /// # Errors
     /// Fails if error
-    pub fn gen_man(_config: &mut Config) -> Result<(), GeneralError> {
+    pub fn gen_man(config: &mut Config, custom_dir: Option<PathBuf>) -> Result<(), GeneralError> {
         let cmd = CliArgs::command();
-        let outdir = home_dir().ok_or(GeneralError::new("Cannot get home dir"))?;
-        let outdir = outdir.join(".config").join(".n4n5").join("man");
-        create_dir_all(&outdir)?;
+        let outdir = Commands::generated_dir(custom_dir, "man")?;
 
         man_generate_to(cmd, &outdir)?;
-        println!(
-            "Generated man to {}{}",
-            outdir.display(),
-            std::path::MAIN_SEPARATOR
-        );
+        if !config.quiet {
+            println!(
+                "Generated man to {}{}",
+                outdir.display(),
+                std::path::MAIN_SEPARATOR
+            );
+        }
         Ok(())
     }
 }
@@ -1,22 +1,18 @@
 //! This module contains all the commands that can be executed.
 
-use clap::{CommandFactory, Subcommand};
-use clap_complete::{
-    generate_to,
-    shells::{Bash, Elvish, Fish, PowerShell, Zsh},
-};
-use home::home_dir;
-use std::fs::create_dir_all;
+use clap::Subcommand;
+use std::{fs::create_dir_all, path::PathBuf};
 
-use crate::{cli::CliArgs, commands::list_crates::ListCrates};
+use crate::commands::list_crates::ListCrates;
 use crate::{
     commands::{gh::lib::GhSubCommand, movies::MoviesSubCommand, shortcuts::ShortcutsSubcommand},
     config::Config,
     errors::GeneralError,
 };
 
-use crate::commands::config::ConfigSubcommand;
+use crate::commands::{completions::CompletionShell, config::ConfigSubcommand};
 
+pub(crate) mod completions;
 pub(crate) mod config;
 pub(crate) mod gh;
 pub(crate) mod list_crates;
@@ -51,7 +47,19 @@ pub(crate) enum Commands {
     },
 
     /// generate completions
-    Completions,
+    Completions {
+        /// only generate the completion of this shell
+        #[arg(long, value_name = "SHELL")]
+        shell: Option<CompletionShell>,
+
+        /// print the completion to stdout instead of the config dir
+        #[arg(long, action = clap::ArgAction::SetTrue, requires = "shell")]
+        stdout: bool,
+
+        /// output directory, defaults to `completions` in the config dir
+        #[arg(long, value_name = "DIR", conflicts_with = "stdout")]
+        out_dir: Option<PathBuf>,
+    },
 
     /// Shortcuts subcommand
     #[command(visible_alias = "s")]
@@ -62,7 +70,11 @@ pub(crate) enum Commands {
     },
 
     /// generate man
-    Man,
+    Man {
+        /// output directory, defaults to `man` in the config dir
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
 
     /// list crates subcommand
     #[command(name = "list_crates")]
@@ -98,23 +110,21 @@ pub(crate) enum Commands {
 }
 
 impl Commands {
-    /// Get the music file path
+    /// Get the directory where generated files (man, completions) are written
+    ///
+    /// Defaults to `<config dir>/<name>`, the directory is created if needed
     /// # Errors
-    /// Fails if the file cannot be found
-    pub fn gen_completions(_config: &mut Config) -> Result<(), GeneralError> {
-        let mut cmd = CliArgs::command();
-        let app_name = env!("CARGO_CRATE_NAME");
-        let outdir = home_dir().ok_or(GeneralError::new("Cannot get home dir"))?;
-        let outdir = outdir.join(".config").join(".n4n5").join("completions");
-
-        create_dir_all(&outdir)?;
-        generate_to(Bash, &mut cmd, app_name, &outdir)?;
-        generate_to(Zsh, &mut cmd, app_name, &outdir)?;
-        generate_to(Fish, &mut cmd, app_name, &outdir)?;
-        generate_to(PowerShell, &mut cmd, app_name, &outdir)?;
-        generate_to(Elvish, &mut cmd, app_name, &outdir)?;
-
-        Ok(())
+    /// Fails if the home dir cannot be found or the directory cannot be created
+    pub(crate) fn generated_dir(
+        custom_dir: Option<PathBuf>,
+        name: &str,
+    ) -> Result<PathBuf, GeneralError> {
+        let dir = match custom_dir {
+            Some(custom_dir) => custom_dir,
+            None => Config::get_config_dir()?.join(name),
+        };
+        create_dir_all(&dir)?;
+        Ok(dir)
     }
 
     /// Invoke subcommands
@@ -125,8 +135,12 @@ impl Commands {
             Commands::Config { subcommand } => subcommand.invoke(config),
             Commands::Gh { subcommand } => subcommand.invoke(config),
             Commands::Movies { subcommand } => subcommand.invoke(config),
-            Commands::Completions => Commands::gen_completions(config),
-            Commands::Man => Commands::gen_man(config),
+            Commands::Completions {
+                shell,
+                stdout,
+                out_dir,
+            } => Commands::gen_completions(config, shell, stdout, out_dir),
+            Commands::Man { out_dir } => Commands::gen_man(config, out_dir),
             Commands::Shortcuts { subcommand } => subcommand.run(config),
             Commands::ListCrates(subcommand) => subcommand.list_crates(config),
 
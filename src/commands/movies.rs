@@ -3,9 +3,14 @@
 //! n4n5 movies
 //! ```
 //!
-use std::{collections::BTreeMap, fs::read_to_string, path::PathBuf, process::Command};
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-use clap::{ArgAction, Subcommand};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -13,9 +18,32 @@ use crate::{
     config_path,
     errors::GeneralError,
     get_config_path,
-    utils::{get_input, input_path},
+    utils::{
+        get_input, input_path, input_yes, parse_csv, pretty_print, resolve_editor,
+        table_to_markdown_table,
+    },
 };
 
+/// JSON Schema (draft-07) of the movies file
+const MOVIES_SCHEMA: &str = r#"{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "title": "n4n5 movies",
+  "type": "array",
+  "items": {
+    "type": "object",
+    "required": ["title", "note", "date", "comment"],
+    "properties": {
+      "title": { "type": "string" },
+      "note": { "type": "number" },
+      "date": { "type": "integer", "minimum": 0 },
+      "comment": { "type": "string" },
+      "seen": { "type": ["string", "null"] },
+      "summary": { "type": ["string", "null"] },
+      "runtime": { "type": ["integer", "null"], "minimum": 0 }
+    }
+  }
+}"#;
+
 /// Movies configuration
 #[derive(Deserialize, Serialize, Default)]
 pub struct Movies {
@@ -24,6 +52,12 @@ pub struct Movies {
 
     /// public path to the movies file
     pub public_file_path: Option<String>,
+
+    /// Minimum note of a movie (default 0.0)
+    pub note_min: Option<f64>,
+
+    /// Maximum note of a movie (default 10.0)
+    pub note_max: Option<f64>,
 }
 
 /// Display mode
@@ -38,6 +72,69 @@ pub enum DisplayMode {
     Full,
 }
 
+/// Sort key of movies
+#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+pub enum SortKey {
+    /// Sort by note
+    #[default]
+    Note,
+
+    /// Sort by publication date
+    Date,
+
+    /// Sort by title, ignoring case
+    Title,
+}
+
+/// Export format of movies
+#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+pub enum ExportFormat {
+    /// Markdown table
+    #[default]
+    Markdown,
+
+    /// Pretty json
+    Json,
+}
+
+/// Fields of a movie to add
+///
+/// Missing fields are asked, unless all required fields are given
+#[derive(Parser, Debug, Clone)]
+pub struct AddMovie {
+    /// movie title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// movie note
+    #[arg(long)]
+    note: Option<f64>,
+
+    /// movie publication year
+    #[arg(long)]
+    date: Option<u64>,
+
+    /// comment about the movie
+    #[arg(long)]
+    comment: Option<String>,
+
+    /// seen date
+    #[arg(long)]
+    seen: Option<String>,
+
+    /// summary of the movie
+    #[arg(long)]
+    summary: Option<String>,
+
+    /// runtime of the movie (in minutes)
+    #[arg(long)]
+    runtime: Option<u32>,
+
+    /// never prompt, missing required fields are an error
+    #[arg(long = "non-interactive", action = ArgAction::SetTrue)]
+    non_interactive: bool,
+}
+
 /// All movies data
 pub struct AllMovies {
     /// List of movies
@@ -57,6 +154,64 @@ impl AllMovies {
     }
 }
 
+/// Movies stats
+#[derive(Serialize, Debug)]
+pub struct MovieStats {
+    /// Number of movies
+    pub movies: usize,
+
+    /// Min publication date
+    pub min_date: u64,
+
+    /// Max publication date
+    pub max_date: u64,
+
+    /// Average note
+    pub avg_note: f64,
+
+    /// Median note
+    pub median_note: f64,
+
+    /// Total runtime of seen movies (in minutes)
+    pub total_watch_time: u64,
+
+    /// Average runtime (in minutes), of movies with a runtime
+    pub avg_runtime: Option<f64>,
+
+    /// Percentiles of notes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentiles: Option<NotePercentiles>,
+}
+
+/// Stats of the movies of one year
+#[derive(Serialize, Debug)]
+pub struct YearStats {
+    /// Number of movies
+    pub count: usize,
+
+    /// Average note
+    pub avg_note: f64,
+}
+
+/// Percentiles of notes
+#[derive(Serialize, Debug)]
+pub struct NotePercentiles {
+    /// 10th percentile
+    pub p10: f64,
+
+    /// 25th percentile
+    pub p25: f64,
+
+    /// 50th percentile
+    pub p50: f64,
+
+    /// 75th percentile
+    pub p75: f64,
+
+    /// 90th percentile
+    pub p90: f64,
+}
+
 /// Movie data
 #[derive(Deserialize, Serialize, Debug)]
 pub struct OneMovie {
@@ -77,6 +232,10 @@ pub struct OneMovie {
 
     /// Summary of the movie
     pub summary: Option<String>,
+
+    /// Runtime of the movie (in minutes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<u32>,
 }
 
 impl OneMovie {
@@ -111,18 +270,39 @@ impl OneMovie {
 #[derive(Subcommand, Debug, Clone)]
 pub enum MoviesSubCommand {
     /// add a movie
-    Add,
+    Add(AddMovie),
+    /// remove a movie
+    Remove {
+        /// exact title of the movie
+        title: String,
+    },
     /// open movie file
     Open {
         /// print path of movies file
         #[arg(short = 'p', long = "path", action = ArgAction::SetTrue)]
         show_path: bool,
+
+        /// editor command to use for this run, overriding the configured one
+        #[arg(short = 'e', long = "editor", value_name = "CMD")]
+        editor: Option<String>,
     },
     /// Show stats of movies
     Stats {
         /// print stats as json
         #[arg(short ='j', long = "json", action = ArgAction::SetTrue)]
         print_json: bool,
+
+        /// export stats as pretty json to a file (`-` for stdout)
+        #[arg(short = 'o', long = "output", alias = "export", value_name = "FILE")]
+        export: Option<PathBuf>,
+
+        /// show percentiles of notes
+        #[arg(short = 'p', long = "percentiles", action = ArgAction::SetTrue)]
+        percentiles: bool,
+
+        /// show count and average note per publication year
+        #[arg(long = "by-year", action = ArgAction::SetTrue)]
+        by_year: bool,
     },
     /// Show movies list
     Show {
@@ -135,6 +315,41 @@ pub enum MoviesSubCommand {
         /// show comment
         #[arg(short = 'c', long = "comment", action = ArgAction::SetTrue)]
         show_comment: bool,
+        /// only show movies released from this year
+        #[arg(long = "from", value_name = "YEAR")]
+        from: Option<u64>,
+        /// only show movies released until this year
+        #[arg(long = "to", value_name = "YEAR")]
+        to: Option<u64>,
+        /// sort key
+        #[arg(short = 's', long = "sort", value_enum, default_value_t = SortKey::Note)]
+        sort: SortKey,
+        /// only show the first n movies (0 to show all)
+        #[arg(short = 'l', long = "limit", default_value_t = 0)]
+        limit: usize,
+    },
+    /// Import movies from a file
+    Import {
+        /// file to import
+        path: PathBuf,
+        /// format of the file, only `csv` is supported
+        #[arg(long = "format", default_value = "csv")]
+        format: String,
+    },
+    /// Export movies list
+    Export {
+        /// export format
+        #[arg(long = "format", value_enum, default_value_t = ExportFormat::Markdown)]
+        format: ExportFormat,
+        /// output file (`-` for stdout)
+        #[arg(short = 'o', long = "output", value_name = "FILE", default_value = "-")]
+        output: PathBuf,
+        /// sort key
+        #[arg(short = 's', long = "sort", value_enum, default_value_t = SortKey::Note)]
+        sort: SortKey,
+        /// reverse mode
+        #[arg(short = 'r', long = "reverse", action = ArgAction::SetTrue)]
+        reverse: bool,
     },
     /// Sync movies file
     Sync {
@@ -142,6 +357,28 @@ pub enum MoviesSubCommand {
         #[arg(short ='j', long = "json", action = ArgAction::SetTrue)]
         print_json: bool,
     },
+    /// Search movies by title, comment or summary
+    Search {
+        /// text to search for
+        query: String,
+        /// match case
+        #[arg(short = 's', long = "case-sensitive", action = ArgAction::SetTrue)]
+        case_sensitive: bool,
+    },
+    /// Move old movies to an archive file (dry run by default)
+    Purge {
+        /// archive movies released before this year
+        #[arg(long = "before", value_name = "YEAR")]
+        before: u64,
+        /// archive file, appended to if it exists
+        #[arg(long = "to", value_name = "FILE")]
+        archive_path: PathBuf,
+        /// really purge the movies, the global `--yes` skips the confirmation
+        #[arg(long = "apply", action = ArgAction::SetTrue)]
+        apply: bool,
+    },
+    /// Print the JSON Schema of the movies file
+    Schema,
 }
 
 impl MoviesSubCommand {
@@ -150,15 +387,60 @@ impl MoviesSubCommand {
     /// Error if error in subcommand
     pub fn invoke(self, config: &mut Config) -> Result<(), GeneralError> {
         match self {
-            Self::Add => Movies::add_movie(config),
-            Self::Open { show_path } => Movies::open_movies(config, show_path),
+            Self::Add(args) => Movies::add_movie(config, args),
+            Self::Remove { title } => Movies::remove_movie(config, &title),
+            Self::Open { show_path, editor } => {
+                Movies::open_movies(config, show_path, editor.as_deref())
+            }
             Self::Show {
                 reverse,
                 show_full,
                 show_comment,
-            } => Movies::print_sorted_movies(config, reverse, show_comment, show_full),
-            Self::Stats { print_json } => Movies::print_stats(config, print_json),
+                from,
+                to,
+                sort,
+                limit,
+            } => Movies::print_sorted_movies(
+                config,
+                (sort, reverse),
+                show_comment,
+                show_full,
+                (from, to),
+                limit,
+            ),
+            Self::Stats {
+                print_json,
+                export,
+                percentiles,
+                by_year,
+            } => {
+                if by_year {
+                    Movies::print_stats_by_year(config, print_json, export)
+                } else {
+                    Movies::print_stats(config, print_json, export, percentiles)
+                }
+            }
             Self::Sync { print_json } => Movies::full_sync_movies(config, print_json),
+            Self::Import { path, format } => Movies::import_movies(config, &path, &format),
+            Self::Export {
+                format,
+                output,
+                sort,
+                reverse,
+            } => Movies::export_movies(config, format, &output, (sort, reverse)),
+            Self::Search {
+                query,
+                case_sensitive,
+            } => Movies::search_movies(config, &query, case_sensitive),
+            Self::Purge {
+                before,
+                archive_path,
+                apply,
+            } => Movies::purge_movies(config, before, &archive_path, apply),
+            Self::Schema => {
+                println!("{MOVIES_SCHEMA}");
+                Ok(())
+            }
         }
     }
 }
@@ -173,47 +455,263 @@ impl Movies {
         {
             return Ok(PathBuf::from(movie_path));
         }
-        Err(GeneralError::new("movies path not set"))
+        Err(GeneralError::config("movies path not set"))
     }
 
     /// Add a movie
     /// # Errors
     /// Returns an error if unable to read the movies file
-    fn add_movie(config: &mut Config) -> Result<(), GeneralError> {
+    fn add_movie(config: &mut Config, args: AddMovie) -> Result<(), GeneralError> {
         let file_path = Movies::get_movie_path(config)?;
-        let title = get_input("Title")?;
-        let note = get_input("Note")?.parse()?;
-        let date = get_input("Date")?.parse()?;
-        let comment = get_input("Comment")?;
-        let seen = get_input("Seen")?;
-        let summary = get_input("Summary")?;
+        let complete = args.title.is_some()
+            && args.note.is_some()
+            && args.date.is_some()
+            && args.comment.is_some();
+        let prompt = !complete && !args.non_interactive;
+        let required = |value: Option<String>, name: &str| match value {
+            Some(value) => Ok(value),
+            None if prompt => get_input(name),
+            None => Err(GeneralError::new(format!("missing movie field '{name}'"))),
+        };
+        let title = required(args.title, "Title")?;
+        let note = match args.note {
+            Some(note) => Movies::check_note(config, note)?,
+            None if prompt => Movies::input_note(config)?,
+            None => return Err(GeneralError::new("missing movie field 'Note'")),
+        };
+        let date = match args.date {
+            Some(date) => date,
+            None => required(None, "Date")?.trim().parse()?,
+        };
+        let comment = required(args.comment, "Comment")?;
+        let seen = match args.seen {
+            None if prompt => Some(get_input("Seen")?),
+            seen => seen,
+        };
+        let summary = match args.summary {
+            None if prompt => Some(get_input("Summary")?),
+            summary => summary,
+        };
+        let runtime = match args.runtime {
+            None if prompt => {
+                let runtime = get_input("Runtime (in minutes, empty if unknown)")?;
+                if runtime.trim().is_empty() {
+                    None
+                } else {
+                    Some(runtime.trim().parse()?)
+                }
+            }
+            runtime => runtime,
+        };
         let movie = OneMovie {
             title,
             note,
             date,
             comment,
-            seen: Some(seen),
-            summary: Some(summary),
+            seen,
+            summary,
+            runtime,
         };
         let mut all_movies = Movies::get_all_movies(config)?;
         all_movies.movies.push(movie);
         let movies_file_to_str = serde_json::to_string_pretty(&all_movies.movies)?;
         std::fs::write(&file_path, movies_file_to_str)?;
-        println!("Movie added to '{}'", file_path.display());
+        if !config.quiet {
+            println!("Movie added to '{}'", file_path.display());
+        }
+        Ok(())
+    }
+
+    /// Ask for a note, within the configured range
+    ///
+    /// An out of range note is asked again, or is an error without input
+    /// # Errors
+    /// Returns an error if the note can't be parsed or is out of range
+    fn input_note(config: &Config) -> Result<f64, GeneralError> {
+        let (note_min, note_max) = Movies::note_range(config);
+        loop {
+            let note: f64 = get_input("Note")?.trim().parse()?;
+            if (note_min..=note_max).contains(&note) {
+                return Ok(note);
+            }
+            let msg = format!("Note {note} is not between {note_min} and {note_max}");
+            if !config.use_input {
+                return Err(GeneralError::new(msg));
+            }
+            eprintln!("{msg}");
+        }
+    }
+
+    /// Check that a note is within the configured range
+    /// # Errors
+    /// Returns an error if the note is out of range
+    fn check_note(config: &Config, note: f64) -> Result<f64, GeneralError> {
+        let (note_min, note_max) = Movies::note_range(config);
+        if (note_min..=note_max).contains(&note) {
+            Ok(note)
+        } else {
+            Err(GeneralError::new(format!(
+                "Note {note} is not between {note_min} and {note_max}"
+            )))
+        }
+    }
+
+    /// Get the configured note range, 0 to 10 by default
+    fn note_range(config: &Config) -> (f64, f64) {
+        let movies_config = config.config_data.movies.as_ref();
+        let note_min = movies_config.and_then(|m| m.note_min).unwrap_or(0.0);
+        let note_max = movies_config.and_then(|m| m.note_max).unwrap_or(10.0);
+        (note_min, note_max)
+    }
+
+    /// Import movies from a csv file with a header row
+    ///
+    /// Rows that can't be parsed, or with a note out of the configured range,
+    /// are reported and skipped
+    /// # Errors
+    /// Returns an error if the format is not supported, if the header is invalid,
+    /// or if unable to read or write the movies files
+    fn import_movies(config: &Config, path: &Path, format: &str) -> Result<(), GeneralError> {
+        if format != "csv" {
+            return Err(GeneralError::new(format!(
+                "Unsupported import format '{format}', expected 'csv'"
+            )));
+        }
+        let file_path = Movies::get_movie_path(config)?;
+        let content = read_to_string(path)
+            .map_err(|e| (format!("Unable to open '{}'", path.display()), e))?;
+        let mut records = parse_csv(&content).into_iter();
+        let header = records.next().ok_or("Empty csv file")?;
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+        };
+        let (Some(title_idx), Some(note_idx), Some(date_idx)) =
+            (column("title"), column("note"), column("date"))
+        else {
+            return Err(GeneralError::new(
+                "csv header must contain title, note and date",
+            ));
+        };
+        let (comment_idx, seen_idx, summary_idx) =
+            (column("comment"), column("seen"), column("summary"));
+        let mut all_movies = Movies::get_all_movies(config)?;
+        let (mut imported, mut skipped) = (0, 0);
+        for (line, record) in records.enumerate() {
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            let get = |idx: Option<usize>| {
+                idx.and_then(|idx| record.get(idx))
+                    .map(|field| field.trim().to_string())
+            };
+            let parsed = (|| -> Result<OneMovie, GeneralError> {
+                Ok(OneMovie {
+                    title: get(Some(title_idx))
+                        .filter(|title| !title.is_empty())
+                        .ok_or("missing title")?,
+                    note: Movies::check_note(
+                        config,
+                        get(Some(note_idx)).unwrap_or_default().parse()?,
+                    )?,
+                    date: get(Some(date_idx)).unwrap_or_default().parse()?,
+                    comment: get(comment_idx).unwrap_or_default(),
+                    seen: get(seen_idx).filter(|seen| !seen.is_empty()),
+                    summary: get(summary_idx).filter(|summary| !summary.is_empty()),
+                    runtime: None,
+                })
+            })();
+            match parsed {
+                Ok(movie) => {
+                    all_movies.movies.push(movie);
+                    imported += 1;
+                }
+                // data rows start on line 2
+                Err(e) => {
+                    eprintln!("Skipping row {}: {e}", line + 2);
+                    skipped += 1;
+                }
+            }
+        }
+        let movies_file_to_str = serde_json::to_string_pretty(&all_movies.movies)?;
+        std::fs::write(&file_path, movies_file_to_str)?;
+        if !config.quiet {
+            println!(
+                "Imported {imported} movies to '{}', skipped {skipped} rows",
+                file_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Remove a movie by title
+    ///
+    /// When several movies share the title, the user chooses which one to remove
+    /// # Errors
+    /// Returns an error if no movie matches, if the choice is invalid or not
+    /// possible without input, or if unable to write the movies file
+    fn remove_movie(config: &mut Config, title: &str) -> Result<(), GeneralError> {
+        let file_path = Movies::get_movie_path(config)?;
+        let mut all_movies = Movies::get_all_movies(config)?;
+        let matches = all_movies
+            .movies
+            .iter()
+            .enumerate()
+            .filter(|(_, movie)| movie.title == title)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<usize>>();
+        let idx = match matches.as_slice() {
+            [] => return Err(GeneralError::new(format!("No movie titled '{title}'"))),
+            [idx] => *idx,
+            _ => {
+                for (choice, idx) in matches.iter().enumerate() {
+                    println!("{}: {}", choice + 1, all_movies.movies[*idx].display());
+                }
+                if !config.use_input {
+                    return Err(GeneralError::new(format!(
+                        "{} movies titled '{title}', run without --no-input to choose",
+                        matches.len()
+                    )));
+                }
+                let choice: usize = get_input("Which one to remove?")?.trim().parse()?;
+                *choice
+                    .checked_sub(1)
+                    .and_then(|choice| matches.get(choice))
+                    .ok_or("Invalid choice")?
+            }
+        };
+        let movie = all_movies.movies.remove(idx);
+        let movies_file_to_str = serde_json::to_string_pretty(&all_movies.movies)?;
+        std::fs::write(&file_path, movies_file_to_str)?;
+        if !config.quiet {
+            println!(
+                "Removed '{}' ({}) from '{}'",
+                movie.title,
+                movie.date,
+                file_path.display()
+            );
+        }
         Ok(())
     }
 
     /// Open movie file
     /// # Errors
     /// Returns an error if unable to open the movies file
-    pub fn open_movies(config: &mut Config, show_path: bool) -> Result<(), GeneralError> {
+    pub fn open_movies(
+        config: &mut Config,
+        show_path: bool,
+        editor: Option<&str>,
+    ) -> Result<(), GeneralError> {
         let file_path = Movies::get_movie_path(config)?;
         if show_path {
             println!("{}", file_path.display());
             return Ok(());
         }
-        println!("Opening movies file at {}", file_path.display());
-        let editor = std::env::var("EDITOR").unwrap_or("vi".to_string());
+        if !config.quiet {
+            println!("Opening movies file at {}", file_path.display());
+        }
+        let editor = editor.map_or_else(|| resolve_editor(config), str::to_string);
         Command::new(editor).arg(&file_path).spawn()?.wait()?;
         Ok(())
     }
@@ -243,27 +741,129 @@ impl Movies {
         Ok(AllMovies { movies: all_movies })
     }
 
-    /// Print the movies sorted by note
+    /// Print the movies whose title, comment or summary contains the query
+    /// # Errors
+    /// Returns an error if unable to read the movies file or if no movie matched
+    fn search_movies(
+        config: &Config,
+        query: &str,
+        case_sensitive: bool,
+    ) -> Result<(), GeneralError> {
+        let all_movies = Movies::get_all_movies(config)?;
+        let needle = if case_sensitive {
+            query.to_string()
+        } else {
+            query.to_lowercase()
+        };
+        let contains = |text: &str| {
+            if case_sensitive {
+                text.contains(&needle)
+            } else {
+                text.to_lowercase().contains(&needle)
+            }
+        };
+        let matches = all_movies
+            .movies
+            .iter()
+            .filter(|movie| {
+                contains(&movie.title)
+                    || contains(&movie.comment)
+                    || movie.summary.as_deref().is_some_and(contains)
+            })
+            .collect::<Vec<&OneMovie>>();
+        for movie in &matches {
+            println!("{}", movie.display_comment());
+        }
+        if matches.is_empty() {
+            return Err(GeneralError::new(format!("No movie matching '{query}'")));
+        }
+        if !config.quiet {
+            println!("{} movies matched", matches.len());
+        }
+        Ok(())
+    }
+
+    /// Move the movies released before a year to an archive file
+    /// # Errors
+    /// Returns an error if unable to read or write the movies files
+    fn purge_movies(
+        config: &mut Config,
+        before: u64,
+        archive_path: &Path,
+        apply: bool,
+    ) -> Result<(), GeneralError> {
+        let file_path = Movies::get_movie_path(config)?;
+        let all_movies = Movies::get_all_movies(config)?;
+        let (to_archive, to_keep): (Vec<OneMovie>, Vec<OneMovie>) = all_movies
+            .movies
+            .into_iter()
+            .partition(|movie| movie.date < before);
+        if to_archive.is_empty() {
+            if !config.quiet {
+                println!("No movies released before {before}");
+            }
+            return Ok(());
+        }
+        let archived = AllMovies { movies: to_archive };
+        if !config.quiet {
+            archived.display(&DisplayMode::Short);
+            println!(
+                "{} movies to move to '{}'",
+                archived.movies.len(),
+                archive_path.display()
+            );
+        }
+        if !apply {
+            if !config.quiet {
+                println!("Dry run, use --apply to purge");
+            }
+            return Ok(());
+        }
+        if !input_yes(config.assume_yes, "Purge these movies?")? {
+            if !config.quiet {
+                println!("Purge aborted");
+            }
+            return Ok(());
+        }
+        let mut archive: Vec<OneMovie> = Vec::new();
+        if archive_path.is_file() {
+            let archive_to_str = read_to_string(archive_path)?;
+            if !archive_to_str.trim().is_empty() {
+                archive = serde_json::from_str(&archive_to_str)?;
+            }
+        }
+        let archived_len = archived.movies.len();
+        archive.extend(archived.movies);
+        std::fs::write(archive_path, serde_json::to_string_pretty(&archive)?)?;
+        std::fs::write(&file_path, serde_json::to_string_pretty(&to_keep)?)?;
+        if !config.quiet {
+            println!(
+                "Moved {archived_len} movies to '{}'",
+                archive_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Print the sorted movies, optionally within a range of years
     /// # Errors
     /// Returns an error if unable to read the movies file
     fn print_sorted_movies(
         config: &mut Config,
-        reverse: bool,
+        (sort, reverse): (SortKey, bool),
         show_comment: bool,
         show_full: bool,
+        (from, to): (Option<u64>, Option<u64>),
+        limit: usize,
     ) -> Result<(), GeneralError> {
         let mut all_movies = Movies::get_all_movies(config)?;
-        all_movies.movies.sort_by(|a, b| {
-            if reverse {
-                b.note
-                    .partial_cmp(&a.note)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            } else {
-                a.note
-                    .partial_cmp(&b.note)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }
+        all_movies.movies.retain(|movie| {
+            from.is_none_or(|from| movie.date >= from) && to.is_none_or(|to| movie.date <= to)
         });
+        Movies::sort_movies(&mut all_movies.movies, sort, reverse);
+        if limit > 0 {
+            all_movies.movies.truncate(limit);
+        }
         if show_full {
             all_movies.display(&DisplayMode::Full);
         } else if show_comment {
@@ -271,6 +871,69 @@ impl Movies {
         } else {
             all_movies.display(&DisplayMode::Short);
         }
+        if !config.quiet {
+            println!("{} movies", all_movies.movies.len());
+        }
+        Ok(())
+    }
+
+    /// Sort movies by a key
+    fn sort_movies(movies: &mut [OneMovie], sort: SortKey, reverse: bool) {
+        movies.sort_by(|a, b| {
+            let (a, b) = if reverse { (b, a) } else { (a, b) };
+            match sort {
+                SortKey::Note => a
+                    .note
+                    .partial_cmp(&b.note)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+                SortKey::Date => a.date.cmp(&b.date),
+                SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            }
+        });
+    }
+
+    /// Export the sorted movies to a file (`-` for stdout)
+    /// # Errors
+    /// Returns an error if unable to read the movies file or to write the export
+    fn export_movies(
+        config: &Config,
+        format: ExportFormat,
+        output: &Path,
+        (sort, reverse): (SortKey, bool),
+    ) -> Result<(), GeneralError> {
+        let mut all_movies = Movies::get_all_movies(config)?;
+        Movies::sort_movies(&mut all_movies.movies, sort, reverse);
+        let markdown = match format {
+            ExportFormat::Json => {
+                pretty_print(&all_movies.movies, output)?;
+                return Ok(());
+            }
+            ExportFormat::Markdown => {
+                let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+                let header = ["Note", "Title", "Year", "Seen", "Comment"]
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>();
+                let rows = all_movies.movies.iter().map(|movie| {
+                    vec![
+                        movie.note.to_string(),
+                        cell(&movie.title),
+                        movie.date.to_string(),
+                        cell(movie.seen.as_deref().unwrap_or("")),
+                        cell(&movie.comment),
+                    ]
+                });
+                table_to_markdown_table(std::iter::once(header).chain(rows), 5)?
+            }
+        };
+        if output == Path::new("-") {
+            print!("{markdown}");
+        } else {
+            std::fs::write(output, markdown)?;
+            if !config.quiet {
+                println!("Movies exported to '{}'", output.display());
+            }
+        }
         Ok(())
     }
 
@@ -288,8 +951,11 @@ impl Movies {
 
     /// Get the stats of the movies
     /// # Errors
-    /// Fails if cannot get min date, max date, or convert length
-    fn get_stats(movies: &AllMovies) -> Result<(u64, u64, f64, f64), GeneralError> {
+    /// Fails if there is no movies, or if cannot get min date, max date, or convert length
+    fn get_stats(movies: &AllMovies, with_percentiles: bool) -> Result<MovieStats, GeneralError> {
+        if movies.movies.is_empty() {
+            return Err(GeneralError::new("No movies to compute stats from"));
+        }
         // calculate the min date
         let min_date = movies
             .movies
@@ -313,32 +979,151 @@ impl Movies {
         // calculate the median note
         let mut notes = movies.movies.iter().map(|m| m.note).collect::<Vec<f64>>();
         notes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-        let median_note = notes[notes.len() / 2];
+        let middle = notes.len() / 2;
+        let median_note = if notes.len() % 2 == 0 {
+            f64::midpoint(notes[middle - 1], notes[middle])
+        } else {
+            notes[middle]
+        };
+        // calculate the watch time and the average runtime
+        let total_watch_time = movies
+            .movies
+            .iter()
+            .filter(|m| m.seen.as_ref().is_some_and(|seen| !seen.trim().is_empty()))
+            .filter_map(|m| m.runtime)
+            .map(u64::from)
+            .sum();
+        let runtimes = movies
+            .movies
+            .iter()
+            .filter_map(|m| m.runtime)
+            .collect::<Vec<u32>>();
+        let avg_runtime = if runtimes.is_empty() {
+            None
+        } else {
+            let runtimes_len = f64::from(
+                u32::try_from(runtimes.len())
+                    .map_err(|e| format!("Cannot convert the length of runtimes to a f64 {e}"))?,
+            );
+            Some(runtimes.iter().copied().map(f64::from).sum::<f64>() / runtimes_len)
+        };
+        let percentiles = if with_percentiles {
+            Some(NotePercentiles {
+                p10: Movies::percentile(&notes, 10)?,
+                p25: Movies::percentile(&notes, 25)?,
+                p50: Movies::percentile(&notes, 50)?,
+                p75: Movies::percentile(&notes, 75)?,
+                p90: Movies::percentile(&notes, 90)?,
+            })
+        } else {
+            None
+        };
 
-        Ok((min_date, max_date, avg_note, median_note))
+        Ok(MovieStats {
+            movies: movies.movies.len(),
+            min_date,
+            max_date,
+            avg_note,
+            median_note,
+            total_watch_time,
+            avg_runtime,
+            percentiles,
+        })
+    }
+
+    /// Get a percentile of sorted notes, with linear interpolation between ranks
+    /// # Errors
+    /// Fails if there is no notes
+    fn percentile(sorted_notes: &[f64], percent: usize) -> Result<f64, GeneralError> {
+        let last = sorted_notes
+            .len()
+            .checked_sub(1)
+            .ok_or("Cannot compute percentile without movies")?;
+        let rank = percent * last;
+        let (low, remainder) = (rank / 100, rank % 100);
+        let low_note = sorted_notes[low];
+        let high_note = sorted_notes.get(low + 1).copied().unwrap_or(low_note);
+        let fraction = f64::from(
+            u8::try_from(remainder)
+                .map_err(|e| format!("Cannot convert percentile rank to a f64 {e}"))?,
+        ) / 100.0;
+        Ok(low_note + (high_note - low_note) * fraction)
     }
 
     /// Print the stats of the movies
     /// # Errors
     /// Returns an error if unable to read the movies file
-    fn print_stats(config: &mut Config, is_json: bool) -> Result<(), GeneralError> {
+    fn print_stats(
+        config: &mut Config,
+        is_json: bool,
+        export: Option<PathBuf>,
+        percentiles: bool,
+    ) -> Result<(), GeneralError> {
+        let movies = Movies::get_all_movies(config)?;
+        let stats = Movies::get_stats(&movies, percentiles)?;
+        if let Some(export_path) = export {
+            pretty_print(&stats, &export_path)?;
+        } else if is_json {
+            println!("{}", serde_json::to_string(&stats)?);
+        } else {
+            println!("Number of movies: {}", stats.movies);
+            println!("Min date: {}", stats.min_date);
+            println!("Max date: {}", stats.max_date);
+            println!("Average note: {:.3}", stats.avg_note);
+            println!("Median note: {:.3}", stats.median_note);
+            println!(
+                "Total watch time: {}h{:02}",
+                stats.total_watch_time / 60,
+                stats.total_watch_time % 60
+            );
+            if let Some(avg_runtime) = stats.avg_runtime {
+                println!("Average runtime: {avg_runtime:.0} min");
+            }
+            if let Some(p) = &stats.percentiles {
+                println!(
+                    "Percentiles: p10 {:.3}, p25 {:.3}, p50 {:.3}, p75 {:.3}, p90 {:.3}",
+                    p.p10, p.p25, p.p50, p.p75, p.p90
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Print the count and the average note of the movies per publication year
+    /// # Errors
+    /// Returns an error if unable to read the movies file
+    fn print_stats_by_year(
+        config: &mut Config,
+        is_json: bool,
+        export: Option<PathBuf>,
+    ) -> Result<(), GeneralError> {
         let movies = Movies::get_all_movies(config)?;
-        let (min_date, max_date, avg_note, median_note) = Movies::get_stats(&movies)?;
-        if is_json {
-            let stats = serde_json::json!({
-                "movies": movies.movies.len(),
-                "min_date": min_date,
-                "max_date": max_date,
-                "avg_note": avg_note,
-                "median_note": median_note,
-            });
-            println!("{stats}");
+        let mut stats_by_year = BTreeMap::new();
+        for (date, movies) in Movies::group_movies_by_date(&movies) {
+            let movies_len = f64::from(
+                u32::try_from(movies.len())
+                    .map_err(|e| format!("Cannot convert the length of movies to a f64 {e}"))?,
+            );
+            let avg_note = movies.iter().map(|m| m.note).sum::<f64>() / movies_len;
+            stats_by_year.insert(
+                date,
+                YearStats {
+                    count: movies.len(),
+                    avg_note,
+                },
+            );
+        }
+        if let Some(export_path) = export {
+            pretty_print(&stats_by_year, &export_path)?;
+        } else if is_json {
+            println!("{}", serde_json::to_string(&stats_by_year)?);
         } else {
-            println!("Number of movies: {}", movies.movies.len());
-            println!("Min date: {min_date}");
-            println!("Max date: {max_date}");
-            println!("Average note: {avg_note:.3}");
-            println!("Median note: {median_note:.3}");
+            for (date, stats) in &stats_by_year {
+                println!(
+                    "{date}: {} movies, average note {:.3}",
+                    stats.count, stats.avg_note
+                );
+            }
         }
         Ok(())
     }
@@ -349,7 +1134,7 @@ impl Movies {
     /// Fails if updating the config fails
     pub fn full_sync_movies(config: &mut Config, print_json: bool) -> Result<(), GeneralError> {
         Movies::pre_sync_movies(config)?;
-        Movies::sync_movies(config, print_json)
+        Movies::sync_movies(config, print_json, false)
     }
 
     /// Pre sync movies. Used to set the settings
@@ -369,9 +1154,15 @@ impl Movies {
     }
 
     /// Sync the public movie file
+    ///
+    /// With `dry_run`, only prints where the file would be written
     /// # Errors
     /// Returns an error if unable to read the movies file
-    pub fn sync_movies(config: &Config, print_json: bool) -> Result<(), GeneralError> {
+    pub fn sync_movies(
+        config: &Config,
+        print_json: bool,
+        dry_run: bool,
+    ) -> Result<(), GeneralError> {
         if config.debug > 1 {
             println!("Syncing movies");
         }
@@ -399,9 +1190,18 @@ impl Movies {
         if print_json {
             let movies_str = String::from_utf8(buf)?;
             println!("{movies_str}");
+        } else if dry_run {
+            println!(
+                "Would write {} dates ({} bytes) to '{}'",
+                movie_by_date_count.len(),
+                buf.len(),
+                public_movies_path.display()
+            );
         } else {
             std::fs::write(&public_movies_path, buf)?;
-            println!("Movies file saved to '{}'", public_movies_path.display());
+            if !config.quiet {
+                println!("Movies file saved to '{}'", public_movies_path.display());
+            }
         }
         Ok(())
     }
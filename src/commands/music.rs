@@ -7,7 +7,7 @@
 
 use std::{path::PathBuf, process::Command};
 
-use clap::{ArgAction, Subcommand};
+use clap::{ArgAction, Subcommand, ValueEnum};
 use music_exporter::{MusicExporter, PlatformType};
 use serde::{Deserialize, Serialize};
 use tokio::runtime::Runtime;
@@ -16,7 +16,7 @@ use crate::{
     config::Config,
     config_path,
     errors::GeneralError,
-    utils::{input_no, input_path},
+    utils::{input_no, input_path, resolve_editor},
 };
 
 /// Movies configuration
@@ -33,7 +33,11 @@ pub struct MusicCliCommand {
 #[derive(Subcommand, Debug, Clone)]
 pub enum MusicSubcommand {
     /// Save music
-    Sync,
+    Sync {
+        /// Only sync these platforms (comma-separated), without asking
+        #[arg(long = "platforms", value_name = "PLATFORMS")]
+        platforms: Option<String>,
+    },
 
     /// Open music file
     Open {
@@ -49,7 +53,13 @@ impl MusicSubcommand {
     /// Error if error in subcommand
     pub fn invoke(self, config: &mut Config) -> Result<(), GeneralError> {
         match self {
-            MusicSubcommand::Sync => MusicCliCommand::sync_music(config, None),
+            MusicSubcommand::Sync { platforms } => {
+                let platforms = platforms
+                    .as_deref()
+                    .map(MusicCliCommand::parse_platforms)
+                    .transpose()?;
+                MusicCliCommand::sync_music(config, None, platforms)
+            }
             MusicSubcommand::Open { show_path_only } => {
                 MusicCliCommand::open_music_file(config, show_path_only)
             }
@@ -81,28 +91,62 @@ impl MusicCliCommand {
             println!("{}", music_file.display());
             return Ok(());
         }
-        println!("Opening music file at {}", music_file.display());
-        let editor = std::env::var("EDITOR").unwrap_or("vi".to_string());
+        if !config.quiet {
+            println!("Opening music file at {}", music_file.display());
+        }
+        let editor = resolve_editor(config);
         Command::new(editor).arg(&music_file).spawn()?.wait()?;
         Ok(())
     }
 
+    /// Parse a comma-separated list of platforms
+    /// # Errors
+    /// Fails if a platform name is invalid
+    pub fn parse_platforms(platforms: &str) -> Result<Vec<PlatformType>, GeneralError> {
+        platforms
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                PlatformType::from_str(name, true).map_err(|_| {
+                    let choices = PlatformType::value_variants()
+                        .iter()
+                        .filter_map(ValueEnum::to_possible_value)
+                        .map(|value| value.get_name().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    GeneralError::new(format!(
+                        "Invalid platform '{name}', valid platforms are: {choices}"
+                    ))
+                })
+            })
+            .collect()
+    }
+
     /// Sync music
+    ///
+    /// When `platforms` is set, only these platforms are synced, without asking
     /// # Errors
     /// Fails if the music file cannot be found
-    pub fn sync_music(config: &mut Config, sync_all: Option<bool>) -> Result<(), GeneralError> {
+    pub fn sync_music(
+        config: &mut Config,
+        sync_all: Option<bool>,
+        platforms: Option<Vec<PlatformType>>,
+    ) -> Result<(), GeneralError> {
         let rt = Runtime::new()?;
 
         let music_file = MusicCliCommand::get_music_file_path(config)?;
         let env_path = config_path!(config, music, MusicCliCommand, env_path, "the env path");
 
-        println!("music file: '{}'", music_file.display());
-        if let Some(true) = sync_all {
-            let platforms = vec![
+        if !config.quiet {
+            println!("music file: '{}'", music_file.display());
+        }
+        if sync_all == Some(true) || platforms.is_some() {
+            let platforms = platforms.unwrap_or(vec![
                 PlatformType::Deezer,
                 PlatformType::Spotify,
                 PlatformType::Youtube,
-            ];
+            ]);
             rt.block_on(async {
                 env_logger::builder()
                     .filter_level(log::LevelFilter::Info)
@@ -120,8 +164,13 @@ impl MusicCliCommand {
                 PlatformType::Spotify,
                 PlatformType::Youtube,
             ] {
-                if input_no(format!("Should we sync platform: {platform}?"))? {
-                    println!("Skipping platform: {platform}");
+                if input_no(
+                    config.assume_yes,
+                    format!("Should we sync platform: {platform}?"),
+                )? {
+                    if !config.quiet {
+                        println!("Skipping platform: {platform}");
+                    }
                     continue;
                 }
                 rt.block_on(async {
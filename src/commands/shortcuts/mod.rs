@@ -15,7 +15,11 @@ pub(crate) enum ShortcutsSubcommand {
 
     /// Sync all
     #[command(visible_alias = "s")]
-    SyncAll,
+    SyncAll {
+        /// Only print what each step would do, without writing files or running commands
+        #[arg(short = 'n', long = "dry-run", action = clap::ArgAction::SetTrue)]
+        dry_run: bool,
+    },
 }
 
 impl ShortcutsSubcommand {
@@ -26,7 +30,7 @@ impl ShortcutsSubcommand {
     /// Returns `GeneralError` if an error occurs during execution
     pub fn run(&self, config: &mut Config) -> Result<(), GeneralError> {
         match self {
-            ShortcutsSubcommand::SyncAll => Self::sync_all(config),
+            ShortcutsSubcommand::SyncAll { dry_run } => Self::sync_all(config, *dry_run),
             #[cfg(feature = "git-mover")]
             ShortcutsSubcommand::SyncGit => {
                 use crate::commands::Commands;
@@ -2,47 +2,210 @@
 
 use std::thread;
 
-use crate::commands::gh::lib::Gh;
+use crate::commands::gh::lib::{Gh, PullsFilter, PullsQueryOptions, SyncedData};
+use crate::commands::gh::queries::MAX_PAGE_SIZE;
 use crate::commands::movies::Movies;
 use crate::commands::shortcuts::ShortcutsSubcommand;
 use crate::config::Config;
 use crate::errors::GeneralError;
+use crate::utils::table_to_markdown_table;
+
+/// Sync steps, in the order of the summary
+const STEP_ORDER: [&str; 3] = ["movies", "github pulls", "github projects"];
+
+/// Outcome of a sync step
+#[derive(Debug)]
+enum StepStatus {
+    /// The step succeeded
+    Ok,
+
+    /// The step did not run, with the reason
+    Skipped(String),
+
+    /// The step failed, with the error message
+    Failed(String),
+}
+
+/// Result of a sync step
+#[derive(Debug)]
+struct StepResult {
+    /// Name of the step
+    name: &'static str,
+
+    /// Outcome of the step
+    status: StepStatus,
+}
+
+impl StepResult {
+    /// Create a step result from the result of the step
+    fn from_result(name: &'static str, result: Result<(), GeneralError>) -> Self {
+        match result {
+            Ok(()) => Self {
+                name,
+                status: StepStatus::Ok,
+            },
+            Err(e) => Self::failed(name, &e),
+        }
+    }
+
+    /// Create a failed step result, the message is kept on one line
+    fn failed(name: &'static str, error: &GeneralError) -> Self {
+        let message = error.to_string();
+        let message = message.strip_prefix("GeneralError: ").unwrap_or(&message);
+        Self {
+            name,
+            status: StepStatus::Failed(message.replace('\n', " ")),
+        }
+    }
+
+    /// Create a skipped step result
+    fn skipped(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            status: StepStatus::Skipped(reason.to_string()),
+        }
+    }
+}
 
 impl ShortcutsSubcommand {
     /// Sync all
+    ///
+    /// Every step runs even if another one fails, a summary is printed at the end.
+    /// With `dry_run`, each step only prints what it would do
     /// # Errors
-    /// Returns an error if any of the subcommands fails
-    pub(crate) fn sync_all(config: &mut Config) -> Result<(), GeneralError> {
+    /// Returns an error if any of the steps failed
+    pub(crate) fn sync_all(config: &mut Config, dry_run: bool) -> Result<(), GeneralError> {
         config.use_input = false;
         if config.debug > 1 {
             println!("Syncing all");
         }
+        let mut steps = Vec::new();
 
-        if config.config_data.movies.is_some() {
-            Movies::pre_sync_movies(config)?;
-        }
+        let sync_movies = if config.config_data.movies.is_none() {
+            steps.push(StepResult::skipped("movies", "not configured"));
+            false
+        } else if let Err(e) = Movies::pre_sync_movies(config) {
+            steps.push(StepResult::failed("movies", &e));
+            false
+        } else {
+            true
+        };
         // if config.config_data.sync.is_some() {
         // SyncCliCommand::pre_save_files(config)?;
         // SyncCliCommand::pre_sync_programs(config)?;
         // }
-        if config.config_data.gh.is_some() {
-            Gh::pre_sync_github(config)?;
-        }
+        let mut pre_sync_github = |name, data| {
+            if config.config_data.gh.is_none() {
+                steps.push(StepResult::skipped(name, "not configured"));
+                false
+            } else if let Err(e) = Gh::pre_sync_github(config, data, dry_run) {
+                steps.push(StepResult::failed(name, &e));
+                false
+            } else {
+                true
+            }
+        };
+        let sync_pulls = pre_sync_github("github pulls", SyncedData::Pulls);
+        let sync_projects = pre_sync_github("github projects", SyncedData::Projects);
 
         // real sync
-        thread::scope(|s| {
-            if config.config_data.movies.is_some() {
-                s.spawn(|| Movies::sync_movies(config, false));
+        let results = thread::scope(|s| {
+            let mut handles = Vec::new();
+            if sync_movies {
+                handles.push((
+                    "movies",
+                    s.spawn(|| Movies::sync_movies(config, false, dry_run)),
+                ));
             }
             // if config.config_data.sync.is_some() {
             // s.spawn(|| SyncCliCommand::save_files(config));
             // s.spawn(|| SyncCliCommand::sync_programs(config));
             // }
-            if config.config_data.gh.is_some() {
-                s.spawn(|| Gh::save_pulls(config));
-                s.spawn(|| Gh::save_projects(config, false));
+            if sync_pulls {
+                handles.push((
+                    "github pulls",
+                    s.spawn(|| {
+                        if dry_run {
+                            return Gh::dry_run_sync(config, SyncedData::Pulls);
+                        }
+                        Gh::save_pulls(
+                            config,
+                            None,
+                            PullsQueryOptions::default(),
+                            None,
+                            &PullsFilter::default(),
+                        )
+                    }),
+                ));
+            }
+            if sync_projects {
+                handles.push((
+                    "github projects",
+                    s.spawn(|| {
+                        if dry_run {
+                            return Gh::dry_run_sync(config, SyncedData::Projects);
+                        }
+                        Gh::save_projects(config, false, false, None, None, false, MAX_PAGE_SIZE)
+                    }),
+                ));
             }
+            handles
+                .into_iter()
+                .map(|(name, handle)| {
+                    let res = handle
+                        .join()
+                        .unwrap_or_else(|_| Err(GeneralError::new("sync thread panicked")));
+                    (name, res)
+                })
+                .collect::<Vec<_>>()
         });
-        Ok(())
+        for (name, res) in results {
+            let synced = match name {
+                "github pulls" => Some(SyncedData::Pulls),
+                "github projects" => Some(SyncedData::Projects),
+                _ => None,
+            };
+            let res = match synced {
+                Some(data) if res.is_ok() && !dry_run => Gh::record_sync(config, data),
+                _ => res,
+            };
+            steps.push(StepResult::from_result(name, res));
+        }
+
+        steps.sort_by_key(|step| STEP_ORDER.iter().position(|name| *name == step.name));
+        Self::report_steps(config, &steps)
+    }
+
+    /// Print a summary table of the sync steps
+    /// # Errors
+    /// Returns an error listing the failed steps, if any
+    fn report_steps(config: &Config, steps: &[StepResult]) -> Result<(), GeneralError> {
+        if !config.quiet {
+            let header = ["Step", "Status", "Details"].map(String::from).to_vec();
+            let rows = steps.iter().map(|step| {
+                let (status, details) = match &step.status {
+                    StepStatus::Ok => ("ok", String::new()),
+                    StepStatus::Skipped(reason) => ("skipped", reason.clone()),
+                    StepStatus::Failed(message) => ("failed", message.clone()),
+                };
+                vec![step.name.to_string(), status.to_string(), details]
+            });
+            print!(
+                "{}",
+                table_to_markdown_table(std::iter::once(header).chain(rows), 3)?
+            );
+        }
+        let failed_names = steps
+            .iter()
+            .filter(|step| matches!(step.status, StepStatus::Failed(_)))
+            .map(|step| step.name)
+            .collect::<Vec<_>>();
+        if failed_names.is_empty() {
+            return Ok(());
+        }
+        Err(GeneralError::new(format!(
+            "Sync failed for: {}",
+            failed_names.join(", ")
+        )))
     }
 }
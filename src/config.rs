@@ -7,14 +7,45 @@ use crate::{
 use home::home_dir;
 use serde::{Deserialize, Serialize};
 use std::{
-    fs::{File, create_dir_all, read_to_string},
+    fs::{OpenOptions, canonicalize, create_dir_all, metadata, read_to_string, rename},
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str,
 };
 
+/// Template of the configuration file, with all the known keys commented out
+pub(crate) const CONFIG_TEMPLATE: &str = r#"# n4n5 configuration
+
+# editor used to open files, defaults to $VISUAL, $EDITOR then vi
+# editor = "vi"
+
+[movies]
+# file_path = "/path/to/movies.json"
+# public_file_path = "/path/to/public_movies.json"
+# note_min = 0.0
+# note_max = 10.0
+
+[gh]
+# username = "Its-Just-Nans"
+# file_pulls = "/path/to/pulls.json"
+# file_projects = "/path/to/projects.json"
+# file_projects_disk = "/path/to/projects_disk.json"
+# file_profile = "/path/to/profile.json"
+# file_stars = "/path/to/stars.json"
+# token = "ghp_..."
+# host = "github.example.com"
+# retries = 3
+# retry_delay = 2
+# timeout = 30
+
+[music]
+# music_file = "/path/to/music.json"
+# env_path = "/path/to/.env"
+"#;
+
 /// Configuration object
 /// It's linked to a configuration file
+#[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     /// path to the configuration file
     #[allow(clippy::struct_field_names)]
@@ -24,10 +55,19 @@ pub struct Config {
     #[allow(clippy::struct_field_names)]
     pub config_data: ConfigData,
 
+    /// Raw content of the configuration file, its unknown keys are kept when saving
+    pub(crate) raw_data: serde_json::Value,
+
     /// Turn debugging information on
     pub debug: u8,
     /// whether to use input for configuration
     pub use_input: bool,
+    /// Suppress informational prints
+    pub quiet: bool,
+    /// Answer yes to every y/n prompt
+    pub assume_yes: bool,
+    /// Whether colored output is allowed, every ANSI escape must check it
+    pub color: bool,
 }
 
 /// Configuration
@@ -35,6 +75,9 @@ pub struct Config {
 /// The configuration is separated into different sections
 #[derive(Deserialize, Serialize, Default)]
 pub struct ConfigData {
+    /// Editor used to open files, defaults to `$VISUAL`, `$EDITOR` then `vi`
+    pub editor: Option<String>,
+
     /// Movies configuration
     pub movies: Option<Movies>,
 
@@ -46,63 +89,167 @@ pub struct ConfigData {
 }
 
 impl Config {
-    /// Create a new Config object from the default path
+    /// Create a new Config object
+    ///
+    /// The path is, in order, `config_path`, the `N4N5_CONFIG` env var, then the default path.
+    /// A `.json` file is parsed as JSON, any other file as TOML
     /// # Errors
     /// Error if the file can't be opened
+    #[allow(clippy::fn_params_excessive_bools)]
     pub fn try_new(
         config_path: Option<PathBuf>,
         debug: u8,
         use_input: bool,
+        quiet: bool,
+        assume_yes: bool,
+        color: bool,
     ) -> Result<Self, GeneralError> {
-        let config_path = match config_path {
+        let env_config_path = std::env::var("N4N5_CONFIG")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .map(PathBuf::from);
+        let config_path = match config_path.or(env_config_path) {
             Some(p) => p,
             None => Config::get_config_path()?,
         };
         let contents = read_to_string(&config_path)
             .map_err(|e| (format!("Unable to open '{}'", config_path.display()), e))?;
-        let config_data = toml::from_str(&contents)?;
+        let (config_data, raw_data) = if !Config::is_json_path(&config_path) {
+            let raw: toml::Table = toml::from_str(&contents)?;
+            (toml::from_str(&contents)?, serde_json::to_value(raw)?)
+        } else if contents.trim().is_empty() {
+            (
+                ConfigData::default(),
+                serde_json::Value::Object(serde_json::Map::new()),
+            )
+        } else {
+            (
+                serde_json::from_str(&contents)?,
+                serde_json::from_str(&contents)?,
+            )
+        };
         Ok(Config {
             config_path,
             config_data,
+            raw_data,
             debug,
             use_input,
+            quiet,
+            assume_yes,
+            color,
         })
     }
 
-    /// Save the config data to the config file
+    /// Whether a config file is a JSON file, based on its extension
+    pub fn is_json_path(config_path: &Path) -> bool {
+        config_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    }
+
+    /// Save the config data to the config file, as JSON or TOML depending on its extension
+    ///
+    /// The data is merged into the raw file content, so unknown keys are kept, even in known sections
     /// # Errors
     /// Returns an error if the file can't be written to
     pub fn save(&self) -> Result<(), GeneralError> {
-        let config_str = toml::to_string(&self.config_data)?;
-        let mut file = File::create(&self.config_path)?;
-        file.write_all(config_str.as_bytes())?;
+        let mut merged = self.raw_data.clone();
+        merge_json(&mut merged, serde_json::to_value(&self.config_data)?);
+        self.save_data(&merged)
+    }
+
+    /// Save any data to the config file, as JSON or TOML depending on its extension
+    /// # Errors
+    /// Returns an error if the data can't be serialized or the file can't be written to
+    pub(crate) fn save_data<T: Serialize>(&self, data: &T) -> Result<(), GeneralError> {
+        let config_str = if Config::is_json_path(&self.config_path) {
+            serde_json::to_string_pretty(data)?
+        } else {
+            toml::to_string(data)?
+        };
+        self.write_atomic(&config_str)
+    }
+
+    /// Write `content` to the config file atomically, keeping its permissions
+    ///
+    /// A new file is only readable by its owner
+    /// # Errors
+    /// Returns an error if the file can't be written to
+    pub(crate) fn write_atomic(&self, content: &str) -> Result<(), GeneralError> {
+        // a symlinked config (dotfiles) is written through, not replaced by a regular file
+        let config_path =
+            canonicalize(&self.config_path).unwrap_or_else(|_| self.config_path.clone());
+        // write to a sibling temp file then rename it, so a crash never leaves a truncated config
+        let mut tmp_name = config_path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_name);
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        // the config may hold a token, never make it readable by others
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path)?;
+        // keep the permissions of the existing config
+        if let Ok(metadata) = metadata(&config_path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        rename(&tmp_path, &config_path)?;
         Ok(())
     }
 
+    /// Get the n4n5 config directory
+    ///
+    /// It is `$XDG_CONFIG_HOME/.n4n5`, or `~/.config/.n4n5` when the variable is unset
+    /// # Errors
+    /// Error if the home directory can't be found
+    pub fn get_config_dir() -> Result<PathBuf, GeneralError> {
+        // the XDG spec ignores relative paths
+        let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute());
+        let config_home = match xdg_config_home {
+            Some(path) => path,
+            None => match home_dir() {
+                Some(path) if !path.as_os_str().is_empty() => path.join(".config"),
+                _ => {
+                    return Err(GeneralError::config(
+                        "Unable to get your home dir! home::home_dir() isn't working",
+                    ));
+                }
+            },
+        };
+        Ok(config_home.join(".n4n5"))
+    }
+
     /// Get the path to the config file
     /// # Errors
     /// Error if the home directory can't be found
     pub fn get_config_path() -> Result<PathBuf, GeneralError> {
-        let home_dir = match home_dir() {
-            Some(path) if !path.as_os_str().is_empty() => path,
-            _ => {
-                return Err(GeneralError::new(
-                    "Unable to get your home dir! home::home_dir() isn't working",
-                ));
-            }
-        };
-        let config_directory = home_dir.join(".config").join(".n4n5");
+        let config_directory = Config::get_config_dir()?;
         let config_path = config_directory.join("config.toml");
         create_dir_all(config_directory).map_err(|e| format!("Unable to create config dir {e}"))?;
         if !config_path.exists() {
-            let mut file =
-                File::create(&config_path).map_err(|e| ("Unable to create config file", e))?;
-            file.write_all(b"")
-                .map_err(|e| ("Unable to write to config file", e))?;
+            let mut options = OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            // the config may hold a token, never make it readable by others
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            options
+                .open(&config_path)
+                .map_err(|e| ("Unable to create config file", e))?;
         }
         Ok(config_path)
     }
 
+    /// Get a cache directory under the config directory, without creating it
+    /// # Errors
+    /// Error if the home directory can't be found
+    pub fn get_cache_dir(name: &str) -> Result<PathBuf, GeneralError> {
+        Ok(Config::get_config_dir()?.join("cache").join(name))
+    }
+
     /// Update the config data and save it to the config file
     /// # Errors
     /// Returns an error if the file can't be written to
@@ -112,3 +259,84 @@ impl Config {
         Ok(())
     }
 }
+
+/// Merge `update` into `base`, objects are merged recursively
+///
+/// A `null` in `update` (an unset known key) removes the key from `base`
+fn merge_json(base: &mut serde_json::Value, update: serde_json::Value) {
+    match (base, update) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(update)) => {
+            for (key, value) in update {
+                if value.is_null() {
+                    base.remove(&key);
+                } else if let Some(base_value) = base.get_mut(&key) {
+                    merge_json(base_value, value);
+                } else {
+                    base.insert(key, value);
+                }
+            }
+        }
+        (base, update) => *base = update,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+mod tests {
+    //! Tests of the config round trip
+
+    use super::Config;
+    use crate::errors::GeneralError;
+    use std::{
+        fs::{read_to_string, remove_file, write},
+        path::PathBuf,
+    };
+
+    /// Write `content` to a unique temp config file named `name`
+    fn temp_config(name: &str, content: &str) -> Result<PathBuf, GeneralError> {
+        let path = std::env::temp_dir().join(format!("n4n5-{}-{name}", std::process::id()));
+        write(&path, content)?;
+        Ok(path)
+    }
+
+    /// Load the config at `path`, set the editor and save it
+    fn load_and_save(path: &PathBuf) -> Result<String, GeneralError> {
+        let mut config = Config::try_new(Some(path.clone()), 0, false, true, false, false)?;
+        config.update(|data| data.editor = Some("nano".to_string()))?;
+        let saved = read_to_string(path)?;
+        remove_file(path)?;
+        Ok(saved)
+    }
+
+    /// Unknown keys of a TOML config survive a save, at the top level and in known sections
+    #[test]
+    fn toml_round_trip_keeps_unknown_keys() -> Result<(), GeneralError> {
+        let path = temp_config(
+            "round-trip.toml",
+            "unknown = 1\n\n[gh]\nusername = \"me\"\ncustom_key = \"kept\"\n\n[experimental]\nflag = true\n",
+        )?;
+        let saved: toml::Table = toml::from_str(&load_and_save(&path)?)?;
+        assert_eq!(saved["editor"].as_str(), Some("nano"));
+        assert_eq!(saved["unknown"].as_integer(), Some(1));
+        assert_eq!(saved["gh"]["username"].as_str(), Some("me"));
+        assert_eq!(saved["gh"]["custom_key"].as_str(), Some("kept"));
+        assert_eq!(saved["experimental"]["flag"].as_bool(), Some(true));
+        Ok(())
+    }
+
+    /// Unknown keys of a JSON config survive a save, including `null` values
+    #[test]
+    fn json_round_trip_keeps_unknown_keys() -> Result<(), GeneralError> {
+        let path = temp_config(
+            "round-trip.json",
+            r#"{"gh": {"username": "me", "custom_key": "kept"}, "x": null, "list": [1, 2]}"#,
+        )?;
+        let saved: serde_json::Value = serde_json::from_str(&load_and_save(&path)?)?;
+        assert_eq!(saved["editor"], "nano");
+        assert_eq!(saved["gh"]["username"], "me");
+        assert_eq!(saved["gh"]["custom_key"], "kept");
+        assert!(saved.get("x").is_some_and(serde_json::Value::is_null));
+        assert_eq!(saved["list"], serde_json::json!([1, 2]));
+        Ok(())
+    }
+}
@@ -2,9 +2,51 @@
 
 use std::{
     num::{ParseFloatError, ParseIntError},
+    str::ParseBoolError,
     string::FromUtf8Error,
 };
 
+/// Category of a [`GeneralError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Config or IO error
+    Config,
+
+    /// Network error
+    Network,
+
+    /// Parse error
+    Parse,
+
+    /// Any other error
+    Other,
+}
+
+impl ErrorKind {
+    /// Category of an error source, from its type
+    fn of_source(from: &(dyn std::error::Error + Send + Sync + 'static)) -> Self {
+        if let Some(error) = from.downcast_ref::<GeneralError>() {
+            error.kind
+        } else if from.is::<std::io::Error>()
+            || from.is::<toml::de::Error>()
+            || from.is::<toml::ser::Error>()
+        {
+            Self::Config
+        } else if from.is::<reqwest::Error>() {
+            Self::Network
+        } else if from.is::<serde_json::Error>()
+            || from.is::<ParseIntError>()
+            || from.is::<ParseFloatError>()
+            || from.is::<ParseBoolError>()
+            || from.is::<FromUtf8Error>()
+        {
+            Self::Parse
+        } else {
+            Self::Other
+        }
+    }
+}
+
 /// General error type for the application
 #[derive(Debug)]
 pub struct GeneralError {
@@ -12,6 +54,8 @@ pub struct GeneralError {
     message: String,
     /// Error source
     from: Option<Box<dyn std::error::Error + Send + Sync>>,
+    /// Error category
+    kind: ErrorKind,
 }
 
 impl GeneralError {
@@ -21,17 +65,54 @@ impl GeneralError {
         Self {
             message,
             from: None,
+            kind: ErrorKind::Other,
+        }
+    }
+
+    /// Create a new [`GeneralError`] instance for a config error
+    ///
+    /// For example a missing or invalid setting
+    pub fn config<S: AsRef<str>>(msg: S) -> Self {
+        Self {
+            kind: ErrorKind::Config,
+            ..Self::new(msg)
         }
     }
 
     /// Create a new [`GeneralError`] instance with a source
+    ///
+    /// The kind of the error comes from the type of the source
     pub fn new_with_source<S: Into<String>, B: std::error::Error + Send + Sync + 'static>(
         message: S,
         from: B,
     ) -> Self {
+        let from: Box<dyn std::error::Error + Send + Sync> = Box::new(from);
         Self {
             message: message.into(),
-            from: Some(Box::new(from)),
+            kind: ErrorKind::of_source(from.as_ref()),
+            from: Some(from),
+        }
+    }
+
+    /// Category of the error
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Process exit code of the error, depending on its kind
+    ///
+    /// - 2 for config and IO errors
+    /// - 3 for network errors
+    /// - 4 for parse errors
+    /// - 1 otherwise
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        match self.kind {
+            ErrorKind::Config => 2,
+            ErrorKind::Network => 3,
+            ErrorKind::Parse => 4,
+            ErrorKind::Other => 1,
         }
     }
 }
@@ -75,13 +156,19 @@ impl From<serde_json::Error> for GeneralError {
 
 impl From<ParseIntError> for GeneralError {
     fn from(value: ParseIntError) -> Self {
-        Self::new(format!("ParseIntError: {value}"))
+        Self::new_with_source("ParseIntError", value)
     }
 }
 
 impl From<ParseFloatError> for GeneralError {
     fn from(value: ParseFloatError) -> Self {
-        Self::new(format!("ParseFloatError: {value}"))
+        Self::new_with_source("ParseFloatError", value)
+    }
+}
+
+impl From<ParseBoolError> for GeneralError {
+    fn from(value: ParseBoolError) -> Self {
+        Self::new_with_source("ParseBoolError", value)
     }
 }
 
@@ -131,3 +218,35 @@ where
         Self::new_with_source(value.0.into(), value.1)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+mod tests {
+    //! Tests for the error exit codes
+
+    use super::{ErrorKind, GeneralError};
+
+    /// Config errors exit with 2, with or without a source
+    #[test]
+    fn config_errors_exit_with_2() {
+        assert_eq!(GeneralError::config("path not set").exit_code(), 2);
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert_eq!(GeneralError::from(io).exit_code(), 2);
+    }
+
+    /// Errors from a source take the kind of the source
+    #[test]
+    fn kind_comes_from_the_source() {
+        let parse = "x".parse::<i32>().map_err(GeneralError::from);
+        assert_eq!(parse.err().map(|e| e.kind()), Some(ErrorKind::Parse));
+        let wrapped = GeneralError::new_with_source("outer", GeneralError::config("inner"));
+        assert_eq!(wrapped.exit_code(), 2);
+    }
+
+    /// Plain errors exit with 1
+    #[test]
+    fn other_errors_exit_with_1() {
+        assert_eq!(GeneralError::new("failed").exit_code(), 1);
+        assert_eq!(GeneralError::from("failed").kind(), ErrorKind::Other);
+    }
+}
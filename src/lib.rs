@@ -24,6 +24,7 @@
 pub(crate) mod cli;
 pub(crate) mod commands;
 pub(crate) mod config;
+pub(crate) mod date;
 pub mod errors;
 pub(crate) mod macros;
 pub(crate) use macros::{config_path, get_config_path};
@@ -1,20 +1,25 @@
 //! This module contains the macros used in the project
 
 /// automatically generate the input path
+///
+/// The path must not be a directory
 macro_rules! config_path {
     ($config:ident, $setting_name: ident, $struct_name: ident, $key_name: ident, $string: expr) => {
         match &$config.config_data.$setting_name {
             Some($struct_name {
                 $key_name: Some(path),
                 ..
-            }) => PathBuf::from(path),
+            }) => $crate::utils::check_not_dir(PathBuf::from(path), $string)?,
             _ => {
-                println!(concat!(
-                    "Please enter the path to the folder where to save ",
-                    $string,
-                    ":"
-                ));
-                let (file_path, path_string) = input_path()?;
+                if $config.use_input {
+                    println!(concat!(
+                        "Please enter the path to the folder where to save ",
+                        $string,
+                        ":"
+                    ));
+                }
+                let (file_path, path_string) = input_path($config.use_input, $string)?;
+                let file_path = $crate::utils::check_not_dir(file_path, $string)?;
                 $config.update(|config_data| {
                     if let Some(local_config) = config_data.$setting_name.as_mut() {
                         local_config.$key_name = Some(path_string);
@@ -33,14 +38,16 @@ macro_rules! config_path {
 
 pub(crate) use config_path;
 /// automatically get the input path
+///
+/// The path must not be a directory
 macro_rules! get_config_path {
     ($config:ident, $setting_name: ident, $struct_name: ident, $key_name: ident, $string: expr) => {
         match &$config.config_data.$setting_name {
             Some($struct_name {
                 $key_name: Some(path),
                 ..
-            }) => Ok(PathBuf::from(path)),
-            _ => Err(GeneralError::new(concat!(
+            }) => $crate::utils::check_not_dir(PathBuf::from(path), $string),
+            _ => Err(GeneralError::config(concat!(
                 "The path ",
                 $string,
                 " is not set"
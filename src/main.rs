@@ -4,7 +4,7 @@ fn main() {
         Ok(_) => {}
         Err(e) => {
             eprintln!("{e}");
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
@@ -6,7 +6,23 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::errors::GeneralError;
+use crate::{config::Config, date::civil_from_days, errors::GeneralError};
+
+/// Resolve the editor to use
+///
+/// Checks, in order, the `editor` config key, `$VISUAL`, `$EDITOR`, then falls back to `vi`.
+/// Empty values are skipped
+pub fn resolve_editor(config: &Config) -> String {
+    let not_empty = |editor: &String| !editor.trim().is_empty();
+    config
+        .config_data
+        .editor
+        .clone()
+        .filter(not_empty)
+        .or_else(|| std::env::var("VISUAL").ok().filter(not_empty))
+        .or_else(|| std::env::var("EDITOR").ok().filter(not_empty))
+        .unwrap_or("vi".to_string())
+}
 
 /// Write date to a file, with pretty json
 /// # Errors
@@ -28,6 +44,9 @@ where
 }
 
 /// Format a table to markdown
+///
+/// Rows may have different lengths: the table is as wide as the longest row
+/// (and at least `columns`), short rows are padded with empty cells
 /// # Errors
 /// Fails if fmt error
 pub fn table_to_markdown_table<I>(table: I, columns: usize) -> Result<String, std::fmt::Error>
@@ -36,18 +55,25 @@ where
 {
     use core::fmt::Write;
     let mut buf = String::new();
-    let max_sizes = table.clone().fold(vec![0; columns], |mut acc, row| {
+    // at least one dash per column, so empty columns keep a valid separator
+    let max_sizes = table.clone().fold(vec![1; columns], |mut acc, row| {
+        if row.len() > acc.len() {
+            acc.resize(row.len(), 1);
+        }
         for (i, cell) in row.iter().enumerate() {
-            acc[i] = acc[i].max(cell.len());
+            acc[i] = acc[i].max(cell.chars().count());
         }
         acc
     });
 
     for (i, row) in table.enumerate() {
-        let line = row
+        let line = max_sizes
             .iter()
-            .zip(&max_sizes)
-            .map(|(s, width)| format!("{s:width$}"))
+            .enumerate()
+            .map(|(j, width)| {
+                let cell = row.get(j).map_or("", String::as_str);
+                format!("{cell:width$}")
+            })
             .collect::<Vec<_>>()
             .join(" | ");
         writeln!(&mut buf, "| {line} |")?;
@@ -65,6 +91,36 @@ where
     Ok(buf)
 }
 
+/// Parse csv content into records, handling quoted fields
+pub fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
 /// Get input from the user with a prompt
 /// # Errors
 /// Returns a [`GeneralError`] if the input fails
@@ -75,14 +131,17 @@ pub fn get_input(text: &str) -> Result<String, GeneralError> {
 
 /// Get input from the user
 /// # Errors
-/// Returns a [`GeneralError`] if the input fails
+/// Returns a [`GeneralError`] if the input fails or stdin is closed
 pub fn input() -> Result<String, GeneralError> {
     use std::io::{Write, stdin, stdout};
     let mut s = String::new();
     let _ = stdout().flush();
-    stdin()
+    let read = stdin()
         .read_line(&mut s)
         .map_err(|e| ("Failed to read line from stdin", e))?;
+    if read == 0 {
+        return Err(GeneralError::new("Unexpected end of input on stdin"));
+    }
     if let Some('\n') = s.chars().next_back() {
         s.pop();
     }
@@ -93,10 +152,15 @@ pub fn input() -> Result<String, GeneralError> {
 }
 
 /// Get a yes input from the user
+///
+/// With `assume_yes`, returns true without reading stdin
 /// # Errors
 /// Returns a [`GeneralError`] if the input fails
-pub fn input_yes<S: AsRef<str>>(prompt: S) -> Result<bool, GeneralError> {
+pub fn input_yes<S: AsRef<str>>(assume_yes: bool, prompt: S) -> Result<bool, GeneralError> {
     use std::io::Write;
+    if assume_yes {
+        return Ok(true);
+    }
     print!("{} (y/n):", prompt.as_ref());
     std::io::stdout().flush()?;
     let s = input()?;
@@ -104,17 +168,26 @@ pub fn input_yes<S: AsRef<str>>(prompt: S) -> Result<bool, GeneralError> {
 }
 
 /// Get a no input from the user
+///
+/// With `assume_yes`, returns false without reading stdin
 /// # Errors
 /// Returns a [`GeneralError`] if the input fails
-pub fn input_no<S: AsRef<str>>(prompt: S) -> Result<bool, GeneralError> {
-    let input_y = input_yes(prompt)?;
+pub fn input_no<S: AsRef<str>>(assume_yes: bool, prompt: S) -> Result<bool, GeneralError> {
+    let input_y = input_yes(assume_yes, prompt)?;
     Ok(!input_y)
 }
 
 /// Get a valid path from the user
+///
+/// Without `use_input`, fails immediately instead of waiting on stdin
 /// # Errors
-/// Returns a [`GeneralError`] if the path does not exist
-pub fn input_path() -> Result<(PathBuf, String), GeneralError> {
+/// Returns a [`GeneralError`] if the path does not exist or if input is disabled
+pub fn input_path(use_input: bool, name: &str) -> Result<(PathBuf, String), GeneralError> {
+    if !use_input {
+        return Err(GeneralError::config(format!(
+            "The path of {name} is not set, and input is disabled"
+        )));
+    }
     let mut s = input()?;
     let mut path = PathBuf::from(&s);
     loop {
@@ -136,3 +209,114 @@ pub fn input_path() -> Result<(PathBuf, String), GeneralError> {
     let path_to_string = path.to_string_lossy().to_string();
     Ok((path, path_to_string))
 }
+
+/// Check that a configured file path is not a directory
+/// # Errors
+/// Returns a [`GeneralError`] if the path is a directory
+pub fn check_not_dir(path: PathBuf, name: &str) -> Result<PathBuf, GeneralError> {
+    if path.is_dir() {
+        return Err(GeneralError::new(format!(
+            "The path of {name} '{}' is a directory, expected a file",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// Current UTC time as an ISO 8601 timestamp (`YYYY-MM-DDTHH:MM:SSZ`)
+pub fn utc_now_iso() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (days, day_secs) = (secs / 86400, secs % 86400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        day_secs / 3600,
+        day_secs % 3600 / 60,
+        day_secs % 60
+    )
+}
+
+/// Wrap `text` in ANSI truecolor escapes for a `#RRGGBB` color
+///
+/// Returns `None` if the color is not a valid hex color
+pub fn paint_hex(text: &str, hex: &str) -> Option<String> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+    Some(format!("\x1b[38;2;{r};{g};{b}m{text}\x1b[0m"))
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+mod tests {
+    //! Tests of the utils
+
+    use super::table_to_markdown_table;
+
+    /// Build a row from string slices
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(ToString::to_string).collect()
+    }
+
+    /// A single row is the header, followed by its separator
+    #[test]
+    fn markdown_table_single_row() -> Result<(), std::fmt::Error> {
+        let table = table_to_markdown_table([row(&["Name", "Stars"])].into_iter(), 2)?;
+        assert_eq!(table, "| Name | Stars |\n| ---- | ----- |\n");
+        Ok(())
+    }
+
+    /// Columns are as wide as their widest cell, counted in chars
+    #[test]
+    fn markdown_table_wide_columns() -> Result<(), std::fmt::Error> {
+        let rows = [
+            row(&["A", "B"]),
+            row(&["long cell", "é"]),
+            row(&["x", "ééé"]),
+        ];
+        let table = table_to_markdown_table(rows.into_iter(), 2)?;
+        assert_eq!(
+            table,
+            "| A         | B   |\n\
+             | --------- | --- |\n\
+             | long cell | é   |\n\
+             | x         | ééé |\n"
+        );
+        Ok(())
+    }
+
+    /// Ragged rows still produce a well formed table
+    #[test]
+    fn markdown_table_ragged_rows() -> Result<(), std::fmt::Error> {
+        let rows = [row(&["A", "B"]), row(&["1"]), row(&["2", "3"])];
+        let table = table_to_markdown_table(rows.into_iter(), 2)?;
+        assert_eq!(table, "| A | B |\n| - | - |\n| 1 |   |\n| 2 | 3 |\n");
+        Ok(())
+    }
+
+    /// A short row is padded with empty cells, up to `columns`
+    #[test]
+    fn markdown_table_short_row() -> Result<(), std::fmt::Error> {
+        let rows = [row(&["A", "B"]), row(&["1"])];
+        let table = table_to_markdown_table(rows.into_iter(), 3)?;
+        assert_eq!(table, "| A | B |   |\n| - | - | - |\n| 1 |   |   |\n");
+        Ok(())
+    }
+
+    /// A row longer than the others widens the whole table
+    #[test]
+    fn markdown_table_long_row() -> Result<(), std::fmt::Error> {
+        let rows = [row(&["A"]), row(&["1", "22", "333"])];
+        let table = table_to_markdown_table(rows.into_iter(), 1)?;
+        assert_eq!(
+            table,
+            "| A |    |     |\n| - | -- | --- |\n| 1 | 22 | 333 |\n"
+        );
+        Ok(())
+    }
+}